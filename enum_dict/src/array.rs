@@ -0,0 +1,102 @@
+use std::marker::PhantomData;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Fixed-size storage backing [`RequiredDict`](crate::RequiredDict) and [`OptionalDict`](crate::OptionalDict)
+///
+/// This trait is sealed. Every implementor has the same layout as `[V; Self::LENGTH]`.
+pub trait DictArray<V>: sealed::Sealed + Sized {
+    /// Number of elements in the array
+    const LENGTH: usize;
+
+    /// Create an array where each element is produced by calling `f` with its index, in index order
+    fn from_fn<F: FnMut(usize) -> V>(f: F) -> Self;
+
+    fn as_slice(&self) -> &[V];
+
+    fn as_mut_slice(&mut self) -> &mut [V];
+}
+
+impl<V, const N: usize> sealed::Sealed for [V; N] {}
+
+impl<V, const N: usize> DictArray<V> for [V; N] {
+    const LENGTH: usize = N;
+
+    fn from_fn<F: FnMut(usize) -> V>(f: F) -> Self {
+        std::array::from_fn(f)
+    }
+
+    fn as_slice(&self) -> &[V] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [V] {
+        self
+    }
+}
+
+/// Storage for `(A, B)` keys: an outer array `O` of inner arrays `I`, flattened in row-major order
+#[repr(transparent)]
+pub struct ProductArray<O, I>(O, PhantomData<I>);
+
+impl<O: Clone, I> Clone for ProductArray<O, I> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<O: Copy, I> Copy for ProductArray<O, I> {}
+
+impl<O, I> sealed::Sealed for ProductArray<O, I> {}
+
+impl<V, O: DictArray<I>, I: DictArray<V>> DictArray<V> for ProductArray<O, I> {
+    const LENGTH: usize = O::LENGTH * I::LENGTH;
+
+    fn from_fn<F: FnMut(usize) -> V>(mut f: F) -> Self {
+        Self(O::from_fn(|i| I::from_fn(|j| f(i * I::LENGTH + j))), PhantomData)
+    }
+
+    fn as_slice(&self) -> &[V] {
+        // SAFETY: every `DictArray<V>` has the layout of `[V; LENGTH]`, so `[I; O::LENGTH]` is `[V; Self::LENGTH]`
+        unsafe { std::slice::from_raw_parts(self.0.as_slice().as_ptr().cast(), Self::LENGTH) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [V] {
+        // SAFETY: see `as_slice`
+        unsafe { std::slice::from_raw_parts_mut(self.0.as_mut_slice().as_mut_ptr().cast(), Self::LENGTH) }
+    }
+}
+
+/// Storage for `Option<K>` keys: the value for `None` followed by the array `A` for `Some(_)`
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct OptionArray<A, V> {
+    none: V,
+    some: A,
+}
+
+impl<A, V> sealed::Sealed for OptionArray<A, V> {}
+
+impl<A: DictArray<V>, V> DictArray<V> for OptionArray<A, V> {
+    const LENGTH: usize = A::LENGTH + 1;
+
+    fn from_fn<F: FnMut(usize) -> V>(mut f: F) -> Self {
+        Self {
+            none: f(0),
+            some: A::from_fn(|index| f(index + 1)),
+        }
+    }
+
+    fn as_slice(&self) -> &[V] {
+        // SAFETY: `A` has the layout of `[V; A::LENGTH]` and directly follows `none` under `repr(C)`, since the size
+        // of `V` is a multiple of its alignment
+        unsafe { std::slice::from_raw_parts((self as *const Self).cast(), Self::LENGTH) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [V] {
+        // SAFETY: see `as_slice`
+        unsafe { std::slice::from_raw_parts_mut((self as *mut Self).cast(), Self::LENGTH) }
+    }
+}
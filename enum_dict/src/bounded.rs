@@ -0,0 +1,41 @@
+use crate::DictKey;
+use crate::intern::{all, intern};
+
+/// An integer in `0..N`, usable as a dictionary key
+///
+/// Keys are named by their decimal value, e.g. `"0"`, `"1"`, ...
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bounded<const N: usize>(usize);
+
+impl<const N: usize> Bounded<N> {
+    /// Create a new Bounded, returning `None` if `value` is not below `N`
+    pub const fn new(value: usize) -> Option<Self> {
+        if value < N { Some(Self(value)) } else { None }
+    }
+
+    pub const fn get(self) -> usize {
+        self.0
+    }
+}
+
+impl<const N: usize> DictKey for Bounded<N> {
+    const LENGTH: usize = N;
+
+    type Array<V> = [V; N];
+
+    fn variants() -> &'static [&'static str] {
+        intern::<Self, _>(|| (0..N).map(|value| &*value.to_string().leak()).collect())
+    }
+
+    fn all() -> &'static [Self] {
+        intern::<Self, _>(all)
+    }
+
+    fn variant_index(self) -> usize {
+        self.0
+    }
+
+    fn from_variant_index(index: usize) -> Option<Self> {
+        Self::new(index)
+    }
+}
@@ -1,49 +1,416 @@
-use std::marker::PhantomData;
+use crate::DictArray;
 
 /// Trait for types that can be used as dictionary keys
-pub trait DictKey {
-    const VARIANTS: &'static [&'static str];
+///
+/// Names and variants are exposed through functions rather than constants, since keys composed from other keys (tuples,
+/// `Option<K>`, nested enums) can only build them lazily.
+pub trait DictKey: Sized + 'static {
+    /// Number of variants
+    const LENGTH: usize;
+
+    /// Array type holding one value per variant, i.e. `[V; Self::LENGTH]`
+    type Array<V>: DictArray<V>;
+
+    /// Names of all variants, in index order
+    fn variants() -> &'static [&'static str];
+
+    /// All variants, in index order
+    fn all() -> &'static [Self];
 
     /// Convert to usize index
     fn variant_index(self) -> usize;
-}
 
-pub(crate) struct DictVisitor<K, V>(PhantomData<(K, V)>);
+    /// Convert from usize index, returning `None` if it is out of range
+    fn from_variant_index(index: usize) -> Option<Self>;
+
+    /// Look up a variant by name, returning `None` if no variant matches
+    ///
+    /// By default only the names in [`variants`](Self::variants) match. Implementations may accept additional names,
+    /// such as aliases; those are honored during deserialization as well.
+    fn from_name(name: &str) -> Option<Self> {
+        Self::variants()
+            .iter()
+            .position(|&variant| variant == name)
+            .and_then(Self::from_variant_index)
+    }
+
+    /// Names other than [`variants`](Self::variants) that [`from_name`](Self::from_name) accepts, such as aliases
+    ///
+    /// Used to suggest close names in a [`ParseKeyError`](crate::ParseKeyError).
+    fn aliases() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Catch-all variant that unknown names parse to, if any
+    ///
+    /// [`OpenDict`](crate::OpenDict) keeps entries parsed to this variant under their original names instead.
+    fn other() -> Option<Self> {
+        None
+    }
+
+    /// Names of the groups declared with `#[enum_dict(group = "...")]`
+    fn groups() -> &'static [&'static str] {
+        &[]
+    }
 
-impl<K, V> DictVisitor<K, V> {
-    pub fn new() -> Self {
-        Self(PhantomData)
+    /// Keys in the group `name`, in index order, or `None` if there is no such group
+    fn group(_name: &str) -> Option<&'static [Self]> {
+        None
     }
+
+    /// Name of this variant, as listed in [`variants`](Self::variants)
+    fn name(self) -> &'static str {
+        Self::variants()[self.variant_index()]
+    }
+
+    /// Doc comment of this variant, or `""` if it has none
+    ///
+    /// Keys nested in a variant share its doc comment.
+    fn doc(self) -> &'static str {
+        ""
+    }
+
+    /// Doc comments of all variants, in index order
+    fn docs() -> &'static [&'static str] {
+        struct Docs;
+        // SAFETY: indexes below LENGTH are all valid keys
+        crate::intern::intern::<(Self, Docs), _>(|| {
+            (0..Self::LENGTH)
+                .map(|index| Self::from_variant_index(index).unwrap().doc())
+                .collect()
+        })
+    }
+
+    /// Metadata of this variant set with `#[enum_dict(meta(key = "value"))]`
+    ///
+    /// Keys nested in a variant share its metadata.
+    fn metadata(self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// Look up the metadata value for `key`
+    fn meta(self, key: &str) -> Option<&'static str> {
+        self.metadata()
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|&(_, value)| value)
+    }
+}
+
+/// Key at `index`, which must be below `K::LENGTH`
+pub(crate) fn key_at<K: DictKey>(index: usize) -> K {
+    // SAFETY: indexes below K::LENGTH are all valid keys
+    K::from_variant_index(index).unwrap()
 }
 
+#[cfg(feature = "serde")]
+pub(crate) use serde_impl::DictVisitor;
+
 #[cfg(feature = "serde")]
 mod serde_impl {
     use std::fmt;
+    use std::marker::PhantomData;
 
     use serde::Deserialize;
     use serde::de::{MapAccess, Visitor};
 
     use super::*;
 
+    pub struct DictVisitor<K, V> {
+        keep_unknown: bool,
+        marker: PhantomData<(K, V)>,
+    }
+
+    impl<K, V> DictVisitor<K, V> {
+        pub fn new() -> Self {
+            Self {
+                keep_unknown: false,
+                marker: PhantomData,
+            }
+        }
+
+        /// Collect entries with unknown keys instead of ignoring them
+        pub fn keep_unknown() -> Self {
+            Self {
+                keep_unknown: true,
+                marker: PhantomData,
+            }
+        }
+    }
+
     impl<'de, K: DictKey, V: Deserialize<'de>> Visitor<'de> for DictVisitor<K, V> {
-        type Value = Vec<Option<V>>;
+        type Value = (K::Array<Option<V>>, Vec<(String, V)>);
 
         fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
             formatter.write_str("a map with optional keys")
         }
 
         fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
-            let mut vec = K::VARIANTS.iter().map(|_| None).collect::<Vec<_>>();
-            while let Some((key, value)) = map.next_entry::<String, V>()? {
-                // ignore unknown keys
-                for (index, &name) in K::VARIANTS.iter().enumerate() {
-                    if name == key {
-                        vec[index] = Some(value);
-                        break;
+            let mut array = K::Array::<Option<V>>::from_fn(|_| None);
+            let mut unknown = vec![];
+            let other_index = K::other().map(K::variant_index).filter(|_| self.keep_unknown);
+            while let Some((name, value)) = map.next_entry::<String, V>()? {
+                match K::from_name(&name).map(K::variant_index) {
+                    Some(index) if Some(index) != other_index => array.as_mut_slice()[index] = Some(value),
+                    // ignore unknown keys unless asked to keep them
+                    _ if self.keep_unknown => unknown.push((name, value)),
+                    _ => {}
+                }
+            }
+            Ok((array, unknown))
+        }
+    }
+}
+
+/// Implement [`DictKey`] for an enum by listing its unit variants, optionally renamed with `= "name"`
+///
+/// The orphan rule only allows implementing `DictKey` for enums defined in the current crate (e.g. generated code). For
+/// enums from other crates, the `struct` form declares a local newtype wrapping the enum and implements `DictKey` for
+/// it instead.
+///
+/// ```
+/// use enum_dict::{DictKey, impl_dict_key};
+///
+/// impl_dict_key!(
+///     #[derive(Clone, Copy)]
+///     pub struct Shutdown(std::net::Shutdown) {
+///         Read = "read",
+///         Write = "write",
+///         Both = "both",
+///     }
+/// );
+///
+/// assert_eq!(Shutdown::variants(), &["read", "write", "both"]);
+/// assert_eq!(Shutdown(std::net::Shutdown::Write).variant_index(), 1);
+/// ```
+///
+/// Variants missing from the list cannot be stored in a dict; [`DictKey::variant_index`] panics on them. Two variants
+/// with the same name fail the build:
+///
+/// ```compile_fail
+/// use enum_dict::impl_dict_key;
+///
+/// enum Dup {
+///     A,
+///     B,
+/// }
+///
+/// impl_dict_key!(Dup { A = "x", B = "x" }); // error: two variants have the same name
+/// ```
+#[macro_export]
+macro_rules! impl_dict_key {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($ty:ty) { $($variant:ident $(= $rename:literal)?),* $(,)? }) => {
+        $(#[$meta])*
+        $vis struct $name(pub $ty);
+
+        impl ::std::convert::From<$ty> for $name {
+            fn from(value: $ty) -> Self {
+                Self(value)
+            }
+        }
+
+        impl ::std::convert::From<$name> for $ty {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        const _: () = $crate::__private::assert_unique_names(&[$($crate::impl_dict_key!(@name $variant $($rename)?)),*]);
+
+        const _: () = {
+            #[allow(non_camel_case_types)]
+            enum Index { $($variant),* }
+
+            impl $crate::DictKey for $name {
+                const LENGTH: usize = [$(stringify!($variant)),*].len();
+
+                type Array<V> = [V; <$name as $crate::DictKey>::LENGTH];
+
+                fn variants() -> &'static [&'static str] {
+                    &[$($crate::impl_dict_key!(@name $variant $($rename)?)),*]
+                }
+
+                fn all() -> &'static [Self] {
+                    &[$($name(<$ty>::$variant)),*]
+                }
+
+                fn variant_index(self) -> usize {
+                    #[allow(unreachable_patterns)]
+                    match self.0 {
+                        $(<$ty>::$variant => Index::$variant as usize,)*
+                        _ => ::std::panic!("variant not listed in impl_dict_key!"),
+                    }
+                }
+
+                fn from_variant_index(index: usize) -> ::std::option::Option<Self> {
+                    $(if index == Index::$variant as usize {
+                        return ::std::option::Option::Some($name(<$ty>::$variant));
+                    })*
+                    ::std::option::Option::None
+                }
+            }
+        };
+    };
+    ($ty:ty { $($variant:ident $(= $rename:literal)?),* $(,)? }) => {
+        $crate::impl_dict_key!(@impl $ty { $($variant $(= $rename)?),* } {});
+    };
+    // `items` are added to the impl, e.g. `doc` by `define_dict_key!`
+    (@impl $ty:ty { $($variant:ident $(= $rename:literal)?),* } { $($items:tt)* }) => {
+        const _: () = $crate::__private::assert_unique_names(&[$($crate::impl_dict_key!(@name $variant $($rename)?)),*]);
+
+        const _: () = {
+            #[allow(non_camel_case_types)]
+            enum Index { $($variant),* }
+
+            impl $crate::DictKey for $ty {
+                const LENGTH: usize = [$(stringify!($variant)),*].len();
+
+                type Array<V> = [V; <$ty as $crate::DictKey>::LENGTH];
+
+                fn variants() -> &'static [&'static str] {
+                    &[$($crate::impl_dict_key!(@name $variant $($rename)?)),*]
+                }
+
+                fn all() -> &'static [Self] {
+                    &[$(<$ty>::$variant),*]
+                }
+
+                fn variant_index(self) -> usize {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        $(<$ty>::$variant => Index::$variant as usize,)*
+                        _ => ::std::panic!("variant not listed in impl_dict_key!"),
                     }
                 }
+
+                fn from_variant_index(index: usize) -> ::std::option::Option<Self> {
+                    $(if index == Index::$variant as usize {
+                        return ::std::option::Option::Some(<$ty>::$variant);
+                    })*
+                    ::std::option::Option::None
+                }
+
+                $($items)*
+            }
+        };
+    };
+    (@name $variant:ident) => {
+        stringify!($variant)
+    };
+    (@name $variant:ident $rename:literal) => {
+        $rename
+    };
+}
+
+/// Join the lines of a doc comment the way the derive does
+pub fn doc_comment(lines: &[&str]) -> String {
+    let lines: Vec<_> = lines
+        .iter()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect();
+    lines.join("\n").trim().to_owned()
+}
+
+/// Fail the build if two of `names` are equal, like the derive does, see `impl_dict_key!`
+pub const fn assert_unique_names(names: &[&str]) {
+    let mut i = 0;
+    while i < names.len() {
+        let mut j = i + 1;
+        while j < names.len() {
+            if str_eq(names[i], names[j]) {
+                panic!("two variants of a DictKey enum have the same name");
             }
-            Ok(vec)
+            j += 1;
         }
+        i += 1;
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
     }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Declare an enum of unit variants implementing [`DictKey`] and `FromStr`, without the derive macro
+///
+/// Variants are optionally renamed with `= "name"`, like in [`impl_dict_key!`]. Doc comments on variants are available
+/// through [`DictKey::doc`], and two variants with the same name fail the build.
+///
+/// ```
+/// use enum_dict::{DictKey, define_dict_key};
+///
+/// define_dict_key! {
+///     #[derive(Debug, Clone, Copy, PartialEq)]
+///     pub enum Color {
+///         Red = "red",
+///         Green,
+///         Blue,
+///     }
+/// }
+///
+/// assert_eq!(Color::variants(), &["red", "Green", "Blue"]);
+/// assert_eq!("red".parse(), Ok(Color::Red));
+/// ```
+///
+/// ```compile_fail
+/// use enum_dict::define_dict_key;
+///
+/// define_dict_key! {
+///     enum Size {
+///         Small = "s",
+///         Short = "s", // error: two variants have the same name
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_dict_key {
+    // collect the `#[doc]` lines among the attributes of a variant
+    (@doc [$($lines:expr),*]) => {
+        &[$($lines),*]
+    };
+    (@doc [$($lines:expr),*] #[doc = $line:expr] $($rest:tt)*) => {
+        $crate::define_dict_key!(@doc [$($lines,)* $line] $($rest)*)
+    };
+    (@doc [$($lines:expr),*] #[$($attr:tt)*] $($rest:tt)*) => {
+        $crate::define_dict_key!(@doc [$($lines),*] $($rest)*)
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$($variant_attr:tt)*])* $variant:ident $(= $rename:literal)?),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($(#[$($variant_attr)*])* $variant),*
+        }
+
+        $crate::impl_dict_key!(@impl $name { $($variant $(= $rename)?),* } {
+            fn doc(self) -> &'static str {
+                static DOCS: ::std::sync::OnceLock<::std::vec::Vec<::std::string::String>> = ::std::sync::OnceLock::new();
+                let docs = DOCS.get_or_init(|| {
+                    ::std::vec![$($crate::__private::doc_comment($crate::define_dict_key!(@doc [] $(#[$($variant_attr)*])*))),*]
+                });
+                &docs[<Self as $crate::DictKey>::variant_index(self)]
+            }
+        });
+
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::ParseKeyError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                <Self as $crate::DictKey>::from_name(s).ok_or_else(|| $crate::ParseKeyError::new::<Self>(s))
+            }
+        }
+    };
 }
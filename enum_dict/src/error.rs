@@ -0,0 +1,131 @@
+use std::fmt::{self, Display};
+
+use crate::DictKey;
+
+/// Error returned when parsing a string that does not name any key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeyError {
+    input: String,
+    key_type: String,
+    suggestions: Vec<&'static str>,
+}
+
+impl ParseKeyError {
+    /// Create an error for `input` failing to parse as `K`, suggesting the closest names and aliases of `K`
+    pub fn new<K: DictKey>(input: &str) -> Self {
+        Self {
+            input: input.to_owned(),
+            key_type: short_type_name(std::any::type_name::<K>()),
+            suggestions: closest_names(input, K::variants().iter().chain(K::aliases())),
+        }
+    }
+
+    /// The string that failed to parse
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Name of the key type, without module paths
+    pub fn key_type(&self) -> &str {
+        &self.key_type
+    }
+
+    /// Names or aliases of the variants closest to the input, if any are close enough
+    pub fn suggestions(&self) -> &[&'static str] {
+        &self.suggestions
+    }
+}
+
+impl Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown {} key `{}`", self.key_type, self.input)?;
+        match self.suggestions.as_slice() {
+            [] => Ok(()),
+            [name] => write!(f, ", did you mean `{name}`?"),
+            names => {
+                write!(f, ", did you mean one of ")?;
+                for (index, name) in names.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "`{name}`")?;
+                }
+                write!(f, "?")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseKeyError {}
+
+/// Error returned when a dict lacks values for keys it requires
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingKeysError {
+    keys: Vec<&'static str>,
+}
+
+impl MissingKeysError {
+    pub(crate) fn new(keys: Vec<&'static str>) -> Self {
+        Self { keys }
+    }
+
+    /// Names of the missing keys, in index order
+    pub fn keys(&self) -> &[&'static str] {
+        &self.keys
+    }
+}
+
+impl Display for MissingKeysError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Missing keys: {}", self.keys.join(", "))
+    }
+}
+
+impl std::error::Error for MissingKeysError {}
+
+/// Strip module paths from a type name, e.g. `core::option::Option<app::Locale>` becomes `Option<Locale>`
+fn short_type_name(name: &str) -> String {
+    let mut parts = name.split("::").collect::<Vec<_>>();
+    let last = parts.pop().unwrap();
+    let mut short = String::new();
+    for part in parts {
+        short.push_str(part.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_'));
+    }
+    short.push_str(last);
+    short
+}
+
+/// Names with the smallest case-insensitive edit distance to `input`, if it is small enough to be a likely typo
+fn closest_names(input: &str, names: impl IntoIterator<Item = &'static &'static str>) -> Vec<&'static str> {
+    let input = input.to_lowercase();
+    let threshold = (input.chars().count() / 3).max(1);
+    let mut closest = vec![];
+    let mut best = threshold + 1;
+    for &name in names {
+        let distance = edit_distance(&input, &name.to_lowercase());
+        if distance < best {
+            best = distance;
+            closest.clear();
+        }
+        if distance == best {
+            closest.push(name);
+        }
+    }
+    closest
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
@@ -0,0 +1,54 @@
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Return type of the closure passed to `try_map`: a [`Result`] or an [`Option`]
+///
+/// This trait is sealed. The dict built by `try_map` is wrapped in the same kind of type, so an `Err` or a `None`
+/// returned by the closure stops the mapping and is returned as is.
+pub trait Fallible: sealed::Sealed {
+    /// Type of the successful value
+    type Output;
+
+    /// What is kept of a failure: the error of a [`Result`], or `()` for an [`Option`]
+    type Residual;
+
+    /// The same kind of type, with `U` as the successful value
+    type Wrapped<U>;
+
+    fn branch(self) -> Result<Self::Output, Self::Residual>;
+
+    fn wrap<U>(result: Result<U, Self::Residual>) -> Self::Wrapped<U>;
+}
+
+impl<T, E> sealed::Sealed for Result<T, E> {}
+
+impl<T, E> Fallible for Result<T, E> {
+    type Output = T;
+    type Residual = E;
+    type Wrapped<U> = Result<U, E>;
+
+    fn branch(self) -> Result<T, E> {
+        self
+    }
+
+    fn wrap<U>(result: Result<U, E>) -> Result<U, E> {
+        result
+    }
+}
+
+impl<T> sealed::Sealed for Option<T> {}
+
+impl<T> Fallible for Option<T> {
+    type Output = T;
+    type Residual = ();
+    type Wrapped<U> = Option<U>;
+
+    fn branch(self) -> Result<T, ()> {
+        self.ok_or(())
+    }
+
+    fn wrap<U>(result: Result<U, ()>) -> Option<U> {
+        result.ok()
+    }
+}
@@ -0,0 +1,166 @@
+use crate::intern::{Segment, all, intern, names};
+use crate::{DictKey, OptionArray, ProductArray};
+
+impl DictKey for bool {
+    const LENGTH: usize = 2;
+
+    type Array<V> = [V; 2];
+
+    fn variants() -> &'static [&'static str] {
+        &["false", "true"]
+    }
+
+    fn all() -> &'static [Self] {
+        &[false, true]
+    }
+
+    fn variant_index(self) -> usize {
+        self as usize
+    }
+
+    fn from_variant_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+/// `None` comes first, followed by `Some(_)` of every inner key, named like `Some.Red`
+impl<K: DictKey + Sync> DictKey for Option<K> {
+    const LENGTH: usize = K::LENGTH + 1;
+
+    type Array<V> = OptionArray<K::Array<V>, V>;
+
+    fn variants() -> &'static [&'static str] {
+        intern::<Self, _>(|| names(&[Segment::Unit("None"), Segment::Nested("Some", K::variants())]))
+    }
+
+    fn all() -> &'static [Self] {
+        intern::<Self, _>(all)
+    }
+
+    fn variant_index(self) -> usize {
+        match self {
+            None => 0,
+            Some(key) => key.variant_index() + 1,
+        }
+    }
+
+    fn from_variant_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(None),
+            _ => K::from_variant_index(index - 1).map(Some),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "None" => Some(None),
+            _ => name.strip_prefix("Some.").and_then(K::from_name).map(Some),
+        }
+    }
+}
+
+/// Keys are ordered lexicographically and named like `Red.Small`
+impl<A: DictKey + Sync, B: DictKey + Sync> DictKey for (A, B) {
+    const LENGTH: usize = A::LENGTH * B::LENGTH;
+
+    type Array<V> = ProductArray<A::Array<B::Array<V>>, B::Array<V>>;
+
+    fn variants() -> &'static [&'static str] {
+        intern::<Self, _>(|| {
+            let segments = A::variants().iter().map(|&name| Segment::Nested(name, B::variants()));
+            names(&segments.collect::<Vec<_>>())
+        })
+    }
+
+    fn all() -> &'static [Self] {
+        intern::<Self, _>(all)
+    }
+
+    fn variant_index(self) -> usize {
+        self.0.variant_index() * B::LENGTH + self.1.variant_index()
+    }
+
+    fn from_variant_index(index: usize) -> Option<Self> {
+        if index >= Self::LENGTH {
+            return None;
+        }
+        Some((
+            A::from_variant_index(index / B::LENGTH)?,
+            B::from_variant_index(index % B::LENGTH)?,
+        ))
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        // component names may contain dots themselves, so try every split
+        name.match_indices('.')
+            .find_map(|(index, _)| Some((A::from_name(&name[..index])?, B::from_name(&name[index + 1..])?)))
+    }
+}
+
+/// Keys are ordered lexicographically and named like `Red.Small.Round`
+impl<A: DictKey + Sync, B: DictKey + Sync, C: DictKey + Sync> DictKey for (A, B, C) {
+    const LENGTH: usize = <(A, B)>::LENGTH * C::LENGTH;
+
+    type Array<V> = ProductArray<<(A, B) as DictKey>::Array<C::Array<V>>, C::Array<V>>;
+
+    fn variants() -> &'static [&'static str] {
+        intern::<Self, _>(|| {
+            let segments = <(A, B)>::variants()
+                .iter()
+                .map(|&name| Segment::Nested(name, C::variants()));
+            names(&segments.collect::<Vec<_>>())
+        })
+    }
+
+    fn all() -> &'static [Self] {
+        intern::<Self, _>(all)
+    }
+
+    fn variant_index(self) -> usize {
+        (self.0, self.1).variant_index() * C::LENGTH + self.2.variant_index()
+    }
+
+    fn from_variant_index(index: usize) -> Option<Self> {
+        if index >= Self::LENGTH {
+            return None;
+        }
+        let (a, b) = <(A, B)>::from_variant_index(index / C::LENGTH)?;
+        Some((a, b, C::from_variant_index(index % C::LENGTH)?))
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        name.match_indices('.').find_map(|(index, _)| {
+            let (a, b) = <(A, B)>::from_name(&name[..index])?;
+            Some((a, b, C::from_name(&name[index + 1..])?))
+        })
+    }
+}
+
+#[cfg(feature = "std-enums")]
+mod std_enums {
+    crate::impl_dict_key!(std::cmp::Ordering { Less, Equal, Greater });
+
+    crate::impl_dict_key!(std::fmt::Alignment { Left, Right, Center });
+
+    crate::impl_dict_key!(std::net::Shutdown { Read, Write, Both });
+
+    crate::impl_dict_key!(std::num::FpCategory {
+        Nan,
+        Infinite,
+        Zero,
+        Subnormal,
+        Normal,
+    });
+
+    crate::impl_dict_key!(std::sync::atomic::Ordering {
+        Relaxed,
+        Release,
+        Acquire,
+        AcqRel,
+        SeqCst,
+    });
+}
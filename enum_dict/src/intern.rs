@@ -0,0 +1,54 @@
+//! Lazily built `'static` tables for keys composed from other keys.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+use crate::DictKey;
+
+static TABLES: LazyLock<RwLock<HashMap<TypeId, &'static (dyn Any + Send + Sync)>>> = LazyLock::new(Default::default);
+
+/// Return the table of `T` built by `init` for the type `K`, building and leaking it on first use
+///
+/// `K` is usually the key type, but any type works, e.g. a local marker type for each table of a key.
+/// `init` runs without holding the lock, so it may intern other tables. If two threads race on the first use, one of
+/// the tables is leaked without being used.
+pub fn intern<K: 'static, T: Sync + 'static>(init: impl FnOnce() -> Vec<T>) -> &'static [T] {
+    let id = TypeId::of::<(K, T)>();
+    if let Some(table) = TABLES.read().unwrap().get(&id) {
+        return table.downcast_ref::<&'static [T]>().unwrap();
+    }
+    let table: &'static [T] = init().leak();
+    let table = *TABLES.write().unwrap().entry(id).or_insert(Box::leak(Box::new(table)));
+    table.downcast_ref::<&'static [T]>().unwrap()
+}
+
+/// Names contributed by one variant of a composite key
+pub enum Segment {
+    /// A unit variant, contributing its own name
+    Unit(&'static str),
+    /// A variant wrapping another key, contributing `prefix.name` for each of the inner names
+    Nested(&'static str, &'static [&'static str]),
+}
+
+/// Build the names of a composite key from its segments
+pub fn names(segments: &[Segment]) -> Vec<&'static str> {
+    let mut names = vec![];
+    for segment in segments {
+        match *segment {
+            Segment::Unit(name) => names.push(name),
+            Segment::Nested(prefix, inner) => {
+                names.extend(inner.iter().map(|name| &*format!("{prefix}.{name}").leak()));
+            }
+        }
+    }
+    names
+}
+
+/// Build all variants of a key from their indexes
+pub fn all<K: DictKey>() -> Vec<K> {
+    // SAFETY: indexes below K::LENGTH are all valid keys
+    (0..K::LENGTH)
+        .map(|index| K::from_variant_index(index).unwrap())
+        .collect()
+}
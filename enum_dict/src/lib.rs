@@ -1,11 +1,33 @@
 #![doc = include_str!("../README.md")]
 
+mod array;
+mod bounded;
 mod dict_key;
-mod optional_dict;
-mod required_dict;
+mod error;
+mod fallible;
+mod impls;
+mod intern;
+mod normalize;
+mod open_dict;
+pub mod optional_dict;
+pub mod required_dict;
 
+pub use array::{DictArray, OptionArray, ProductArray};
+pub use bounded::Bounded;
 pub use dict_key::DictKey;
 #[cfg(feature = "derive")]
 pub use enum_dict_derive::DictKey;
+#[cfg(feature = "from-file")]
+pub use enum_dict_derive::dict_keys_from_file;
+pub use error::{MissingKeysError, ParseKeyError};
+pub use fallible::Fallible;
+pub use open_dict::OpenDict;
 pub use optional_dict::OptionalDict;
 pub use required_dict::RequiredDict;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::dict_key::{assert_unique_names, doc_comment};
+    pub use crate::intern::{Segment, all, intern, names};
+    pub use crate::normalize::{Normalize, normalize};
+}
@@ -0,0 +1,23 @@
+/// How separators are treated when matching key names, mirrored by the derive macro
+#[derive(Clone, Copy)]
+pub enum Normalize {
+    None,
+    /// `-`, `_` and spaces are interchangeable
+    FoldSeparators,
+    /// `-`, `_` and spaces are ignored
+    StripSeparators,
+}
+
+/// Normalize a key name for matching, after lowercasing it if `case_insensitive` is set
+pub fn normalize(name: &str, case_insensitive: bool, rule: Normalize) -> String {
+    let name = if case_insensitive {
+        name.to_lowercase()
+    } else {
+        name.to_owned()
+    };
+    match rule {
+        Normalize::None => name,
+        Normalize::FoldSeparators => name.replace(['-', ' '], "_"),
+        Normalize::StripSeparators => name.replace(['-', '_', ' '], ""),
+    }
+}
@@ -0,0 +1,131 @@
+use std::fmt::Debug;
+use std::ops::{Index, IndexMut};
+
+use crate::{DictKey, OptionalDict};
+
+/// An [`OptionalDict`] that also keeps entries with unknown keys
+///
+/// Deserialization stores entries whose name matches no key, or only the `#[enum_dict(other)]` variant, in a side list
+/// instead of dropping them. They are serialized again after the known entries, so data from a newer producer
+/// round-trips losslessly.
+pub struct OpenDict<K: DictKey, V> {
+    dict: OptionalDict<K, V>,
+    unknown: Vec<(String, V)>,
+}
+
+impl<K: DictKey, V> OpenDict<K, V> {
+    /// Create a new empty OpenDict
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create an OpenDict from known entries and entries with unknown keys
+    pub fn from_parts(dict: OptionalDict<K, V>, unknown: Vec<(String, V)>) -> Self {
+        Self { dict, unknown }
+    }
+
+    /// Split into known entries and entries with unknown keys
+    pub fn into_parts(self) -> (OptionalDict<K, V>, Vec<(String, V)>) {
+        (self.dict, self.unknown)
+    }
+
+    /// Entries with known keys
+    pub fn dict(&self) -> &OptionalDict<K, V> {
+        &self.dict
+    }
+
+    pub fn dict_mut(&mut self) -> &mut OptionalDict<K, V> {
+        &mut self.dict
+    }
+
+    /// Entries with unknown keys, in the order they were read
+    pub fn unknown(&self) -> &[(String, V)] {
+        &self.unknown
+    }
+
+    pub fn unknown_mut(&mut self) -> &mut Vec<(String, V)> {
+        &mut self.unknown
+    }
+}
+
+impl<K: DictKey, V> From<OptionalDict<K, V>> for OpenDict<K, V> {
+    fn from(dict: OptionalDict<K, V>) -> Self {
+        Self::from_parts(dict, vec![])
+    }
+}
+
+impl<K: DictKey, V> Default for OpenDict<K, V> {
+    fn default() -> Self {
+        Self::from_parts(OptionalDict::default(), vec![])
+    }
+}
+
+impl<K: DictKey, V: Clone> Clone for OpenDict<K, V>
+where
+    K::Array<Option<V>>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self::from_parts(self.dict.clone(), self.unknown.clone())
+    }
+}
+
+impl<K: DictKey, V: PartialEq> PartialEq for OpenDict<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.dict == other.dict && self.unknown == other.unknown
+    }
+}
+
+impl<K: DictKey, V: Eq> Eq for OpenDict<K, V> {}
+
+impl<K: DictKey, V> Index<K> for OpenDict<K, V> {
+    type Output = Option<V>;
+
+    fn index(&self, key: K) -> &Self::Output {
+        &self.dict[key]
+    }
+}
+
+impl<K: DictKey, V> IndexMut<K> for OpenDict<K, V> {
+    fn index_mut(&mut self, key: K) -> &mut Self::Output {
+        &mut self.dict[key]
+    }
+}
+
+impl<K: DictKey, V: Debug> Debug for OpenDict<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.dict.named_entries())
+            .entries(self.unknown.iter().map(|(name, value)| (name.as_str(), value)))
+            .finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::ser::SerializeMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+    use crate::dict_key::DictVisitor;
+
+    impl<K: DictKey, V: Serialize> Serialize for OpenDict<K, V> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(self.dict.len() + self.unknown.len()))?;
+            for (name, value) in self.dict.named_entries() {
+                map.serialize_entry(name, value)?;
+            }
+            for (name, value) in &self.unknown {
+                map.serialize_entry(name, value)?;
+            }
+            map.end()
+        }
+    }
+
+    impl<'de, K: DictKey, V: Deserialize<'de>> Deserialize<'de> for OpenDict<K, V> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (array, unknown) = deserializer.deserialize_map(DictVisitor::<K, V>::keep_unknown())?;
+
+            Ok(Self::from_parts(OptionalDict::from_array(array), unknown))
+        }
+    }
+}
@@ -1,15 +1,18 @@
+//! [`OptionalDict`], its iterators and its entry API
+
 use std::fmt::{Debug, Display};
 use std::hash::{Hash, Hasher};
+use std::iter::{Enumerate, FusedIterator};
 use std::marker::PhantomData;
-use std::ops::{Index, IndexMut};
-use std::str::FromStr;
+use std::ops::{Index, IndexMut, Range};
+use std::slice;
 
-use crate::DictKey;
+use crate::dict_key::key_at;
+use crate::{DictArray, DictKey, Fallible, MissingKeysError};
 
 /// A dictionary where keys may or may not have values
-pub struct OptionalDict<K, V> {
-    inner: Vec<Option<V>>,
-    phantom: PhantomData<K>,
+pub struct OptionalDict<K: DictKey, V> {
+    pub(crate) inner: K::Array<Option<V>>,
 }
 
 impl<K, V> OptionalDict<K, V>
@@ -22,27 +25,253 @@ where
     }
 }
 
-impl<K, V> OptionalDict<K, V> {
+impl<K: DictKey, V> OptionalDict<K, V> {
+    /// Create a new OptionalDict by calling `f` for each key
+    pub fn from_fn<F: FnMut(K) -> Option<V>>(mut f: F) -> Self {
+        Self::from_array(K::Array::from_fn(|index| f(key_at(index))))
+    }
+
+    pub(crate) fn from_array(inner: K::Array<Option<V>>) -> Self {
+        Self { inner }
+    }
+
+    /// Return the number of keys with values
+    ///
+    /// This scans every slot. Indexing hands out `&mut Option<V>`, which the dict cannot observe, so it keeps no
+    /// count or occupancy bitmap next to the slots.
     pub fn len(&self) -> usize {
-        self.inner.iter().filter(|&v| v.is_some()).count()
+        self.inner.as_slice().iter().filter(|&v| v.is_some()).count()
     }
 
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Return a reference to the value for `key`, if any
+    pub fn get(&self, key: K) -> Option<&V> {
+        self[key].as_ref()
+    }
+
+    /// Return a mutable reference to the value for `key`, if any
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.inner.as_mut_slice()[key.variant_index()].as_mut()
+    }
+
+    /// Return the key and a reference to its value, if any
+    pub fn get_key_value(&self, key: K) -> Option<(K, &V)> {
+        let index = key.variant_index();
+        self.inner.as_slice()[index]
+            .as_ref()
+            .map(|value| (key_at(index), value))
+    }
+
+    /// Check whether `key` has a value
+    pub fn contains_key(&self, key: K) -> bool {
+        self[key].is_some()
+    }
+
+    /// Set the value for `key`, returning the previous value, if any
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.inner.as_mut_slice()[key.variant_index()].replace(value)
+    }
+
+    /// Remove the value for `key`, returning it, if any
+    pub fn remove(&mut self, key: K) -> Option<V> {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Remove the value for `key`, returning it, if any; the same as [`remove`](Self::remove)
+    pub fn take(&mut self, key: K) -> Option<V> {
+        self.remove(key)
+    }
+
+    /// Remove the value for `key`, returning the key and the value, if any
+    pub fn remove_entry(&mut self, key: K) -> Option<(K, V)> {
+        let index = key.variant_index();
+        let value = self.inner.as_mut_slice()[index].take()?;
+        Some((key_at(index), value))
+    }
+
+    /// Get the entry for `key`, for in-place manipulation
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let index = key.variant_index();
+        let key = key_at(index);
+        let slot = &mut self.inner.as_mut_slice()[index];
+        if slot.is_some() {
+            Entry::Occupied(OccupiedEntry { key, slot })
+        } else {
+            Entry::Vacant(VacantEntry { key, slot })
+        }
+    }
+
+    /// Remove all values
+    pub fn clear(&mut self) {
+        self.inner.as_mut_slice().fill_with(|| None);
+    }
+
+    /// Keep only the entries for which `f` returns `true`
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        for (index, slot) in self.inner.as_mut_slice().iter_mut().enumerate() {
+            if let Some(value) = slot
+                && !f(&key_at(index), value)
+            {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Remove all entries, returning them as an iterator
+    pub fn drain(&mut self) -> IntoIter<K, V> {
+        std::mem::take(self).into_iter()
+    }
+
+    /// Iterate over the entries with values, in index order
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.inner.as_slice().iter().enumerate(),
+            remaining: self.len(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Iterate over the entries with values, with mutable references to them, in index order
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            remaining: self.len(),
+            inner: self.inner.as_mut_slice().iter_mut().enumerate(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Iterate over the keys with values, in index order
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Iterate over the values, in index order
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// Iterate over mutable references to the values, in index order
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut { inner: self.iter_mut() }
+    }
+
+    /// Create a dict by calling `f` on each value, in index order
+    pub fn map<U, F: FnMut(V) -> U>(self, mut f: F) -> OptionalDict<K, U> {
+        self.map_with_key(|_, value| f(value))
+    }
+
+    /// Create a dict by calling `f` on each entry with a value, in index order
+    pub fn map_with_key<U, F: FnMut(K, V) -> U>(self, mut f: F) -> OptionalDict<K, U> {
+        let mut slots = self.inner;
+        OptionalDict::from_array(K::Array::from_fn(|index| {
+            let value = slots.as_mut_slice()[index].take()?;
+            Some(f(key_at(index), value))
+        }))
+    }
+
+    /// Create a dict keeping only the values for which `f` returns `Some`, in index order
+    pub fn filter_map<U, F: FnMut(V) -> Option<U>>(self, mut f: F) -> OptionalDict<K, U> {
+        let mut slots = self.inner;
+        OptionalDict::from_array(K::Array::from_fn(|index| {
+            slots.as_mut_slice()[index].take().and_then(&mut f)
+        }))
+    }
+
+    /// Like [`map`](Self::map), but stop at the first `Err` or `None` returned by `f` and return it
+    pub fn try_map<R: Fallible, F: FnMut(V) -> R>(self, mut f: F) -> R::Wrapped<OptionalDict<K, R::Output>> {
+        let mut residual = None;
+        let mut slots = self.inner;
+        let inner = K::Array::from_fn(|index| {
+            if residual.is_some() {
+                return None;
+            }
+            let value = slots.as_mut_slice()[index].take()?;
+            f(value).branch().map_err(|err| residual = Some(err)).ok()
+        });
+        R::wrap(match residual {
+            Some(residual) => Err(residual),
+            None => Ok(OptionalDict::from_array(inner)),
+        })
+    }
+
+    /// Pair up the values of both dicts for the keys that have values in both
+    pub fn zip<U>(self, other: OptionalDict<K, U>) -> OptionalDict<K, (V, U)> {
+        self.zip_with(other, |value, other| (value, other))
+    }
+
+    /// Combine the values of both dicts with `f` for the keys that have values in both, in index order
+    pub fn zip_with<U, W, F: FnMut(V, U) -> W>(self, other: OptionalDict<K, U>, mut f: F) -> OptionalDict<K, W> {
+        let (mut slots, mut others) = (self.inner, other.inner);
+        OptionalDict::from_array(K::Array::from_fn(|index| {
+            let value = slots.as_mut_slice()[index].take()?;
+            let other = others.as_mut_slice()[index].take()?;
+            Some(f(value, other))
+        }))
+    }
+
+    /// Iterate over the entries of `keys` that have values, e.g. a group generated by `#[enum_dict(group = "...")]`
+    pub fn iter_group<'a>(&'a self, keys: &'a [K]) -> impl Iterator<Item = (K, &'a V)>
+    where
+        K: Clone,
+    {
+        keys.iter()
+            .filter_map(|key| self[key.clone()].as_ref().map(|value| (key.clone(), value)))
+    }
+
+    /// Check that every key in `keys` has a value, e.g. a group generated by `#[enum_dict(group = "...")]`
+    pub fn validate_group(&self, keys: &[K]) -> Result<(), MissingKeysError>
+    where
+        K: Clone,
+    {
+        let names = K::variants();
+        let missing_keys: Vec<_> = keys
+            .iter()
+            .map(|key| key.clone().variant_index())
+            .filter(|&index| self.inner.as_slice()[index].is_none())
+            .map(|index| names[index])
+            .collect();
+        if missing_keys.is_empty() {
+            Ok(())
+        } else {
+            Err(MissingKeysError::new(missing_keys))
+        }
+    }
+
+    /// Iterate over the entries with values by name, fetching the names once rather than once per entry
+    pub(crate) fn named_entries(&self) -> impl Iterator<Item = (&'static str, &V)> {
+        K::variants()
+            .iter()
+            .copied()
+            .zip(self.inner.as_slice())
+            .filter_map(|(name, slot)| Some((name, slot.as_ref()?)))
+    }
 }
 
 impl<K, V, F> From<F> for OptionalDict<K, V>
 where
-    K: DictKey + FromStr,
-    K::Err: Debug,
+    K: DictKey,
     F: Fn(K) -> Option<V>,
 {
     fn from(f: F) -> Self {
-        Self {
-            // SAFETY: K::VARIANTS are all valid keys
-            inner: K::VARIANTS.iter().map(|s| f(s.parse().unwrap())).collect(),
-            phantom: PhantomData,
+        Self::from_fn(f)
+    }
+}
+
+impl<K: DictKey, V> FromIterator<(K, V)> for OptionalDict<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut dict = Self::new();
+        dict.extend(iter);
+        dict
+    }
+}
+
+impl<K: DictKey, V> Extend<(K, V)> for OptionalDict<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
         }
     }
 }
@@ -53,44 +282,47 @@ where
 {
     fn default() -> Self {
         Self {
-            inner: K::VARIANTS.iter().map(|_| None).collect(),
-            phantom: PhantomData,
+            inner: K::Array::from_fn(|_| None),
         }
     }
 }
 
-impl<K, V: Clone> Clone for OptionalDict<K, V> {
+impl<K: DictKey, V> Clone for OptionalDict<K, V>
+where
+    K::Array<Option<V>>: Clone,
+{
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
-            phantom: PhantomData,
         }
     }
 }
 
-impl<K, V: PartialEq> PartialEq for OptionalDict<K, V> {
+impl<K: DictKey, V> Copy for OptionalDict<K, V> where K::Array<Option<V>>: Copy {}
+
+impl<K: DictKey, V: PartialEq> PartialEq for OptionalDict<K, V> {
     fn eq(&self, other: &Self) -> bool {
-        self.inner == other.inner
+        self.inner.as_slice() == other.inner.as_slice()
     }
 }
 
-impl<K, V: Eq> Eq for OptionalDict<K, V> {}
+impl<K: DictKey, V: Eq> Eq for OptionalDict<K, V> {}
 
-impl<K, V: PartialOrd> PartialOrd for OptionalDict<K, V> {
+impl<K: DictKey, V: PartialOrd> PartialOrd for OptionalDict<K, V> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.inner.partial_cmp(&other.inner)
+        self.inner.as_slice().partial_cmp(other.inner.as_slice())
     }
 }
 
-impl<K, V: Ord> Ord for OptionalDict<K, V> {
+impl<K: DictKey, V: Ord> Ord for OptionalDict<K, V> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.inner.cmp(&other.inner)
+        self.inner.as_slice().cmp(other.inner.as_slice())
     }
 }
 
-impl<K, V: Hash> Hash for OptionalDict<K, V> {
+impl<K: DictKey, V: Hash> Hash for OptionalDict<K, V> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.inner.hash(state);
+        self.inner.as_slice().hash(state);
     }
 }
 
@@ -98,47 +330,423 @@ impl<K: DictKey, V> Index<K> for OptionalDict<K, V> {
     type Output = Option<V>;
 
     fn index(&self, key: K) -> &Self::Output {
-        &self.inner[key.variant_index()]
+        &self.inner.as_slice()[key.variant_index()]
     }
 }
 
 impl<K: DictKey, V> IndexMut<K> for OptionalDict<K, V> {
     fn index_mut(&mut self, key: K) -> &mut Self::Output {
-        &mut self.inner[key.variant_index()]
+        &mut self.inner.as_mut_slice()[key.variant_index()]
     }
 }
 
 impl<K: DictKey, V: Debug> Debug for OptionalDict<K, V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_map()
-            .entries(
-                self.inner
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(index, value)| value.as_ref().map(|value| (K::VARIANTS[index], value))),
-            )
-            .finish()
+        f.debug_map().entries(self.named_entries()).finish()
     }
 }
 
 impl<K: DictKey, V: Display> Display for OptionalDict<K, V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{{")?;
-        let mut is_first = true;
-        for (index, value) in self.inner.iter().enumerate() {
-            let Some(value) = value else {
-                continue;
-            };
-            if is_first {
+        for (index, (name, value)) in self.named_entries().enumerate() {
+            if index > 0 {
                 write!(f, ", ")?;
             }
-            write!(f, "{}: {}", K::VARIANTS[index], value)?;
-            is_first = false;
+            write!(f, "{name}: {value}")?;
         }
         write!(f, "}}")
     }
 }
 
+impl<'a, K: DictKey, V> IntoIterator for &'a OptionalDict<K, V> {
+    type Item = (K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K: DictKey, V> IntoIterator for &'a mut OptionalDict<K, V> {
+    type Item = (K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K: DictKey, V> IntoIterator for OptionalDict<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            remaining: self.len(),
+            inner: self.inner,
+            alive: 0..K::LENGTH,
+        }
+    }
+}
+
+/// Iterator over the entries of an [`OptionalDict`] with values, in index order
+pub struct Iter<'a, K, V> {
+    inner: Enumerate<slice::Iter<'a, Option<V>>>,
+    /// Values not yet yielded
+    remaining: usize,
+    marker: PhantomData<fn() -> K>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            remaining: self.remaining,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K: DictKey, V> Iterator for Iter<'a, K, V> {
+    type Item = (K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.inner
+            .find_map(|(index, value)| value.as_ref().map(|value| (key_at(index), value)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.inner
+            .rfind(|(_, value)| value.is_some())
+            .map(|(index, value)| (key_at(index), value.as_ref().unwrap()))
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K: DictKey, V> FusedIterator for Iter<'_, K, V> {}
+
+/// Mutable iterator over the entries of an [`OptionalDict`] with values, in index order
+pub struct IterMut<'a, K, V> {
+    inner: Enumerate<slice::IterMut<'a, Option<V>>>,
+    /// Values not yet yielded
+    remaining: usize,
+    marker: PhantomData<fn() -> K>,
+}
+
+impl<'a, K: DictKey, V> Iterator for IterMut<'a, K, V> {
+    type Item = (K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.inner
+            .find_map(|(index, value)| value.as_mut().map(|value| (key_at(index), value)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.inner
+            .rfind(|(_, value)| value.is_some())
+            .map(|(index, value)| (key_at(index), value.as_mut().unwrap()))
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K: DictKey, V> FusedIterator for IterMut<'_, K, V> {}
+
+/// Owning iterator over the entries of an [`OptionalDict`] with values, in index order
+pub struct IntoIter<K: DictKey, V> {
+    inner: K::Array<Option<V>>,
+    /// Indexes not yet visited
+    alive: Range<usize>,
+    /// Values not yet yielded
+    remaining: usize,
+}
+
+impl<K: DictKey, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let slots = self.inner.as_mut_slice();
+        self.alive
+            .find_map(|index| slots[index].take().map(|value| (key_at(index), value)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let slots = self.inner.as_mut_slice();
+        self.alive
+            .rfind(|&index| slots[index].is_some())
+            .map(|index| (key_at(index), slots[index].take().unwrap()))
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K: DictKey, V> FusedIterator for IntoIter<K, V> {}
+
+/// Iterator over the keys of an [`OptionalDict`] with values, in index order
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: DictKey, V> Iterator for Keys<'_, K, V> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for Keys<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K: DictKey, V> FusedIterator for Keys<'_, K, V> {}
+
+/// Iterator over the values of an [`OptionalDict`], in index order
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K: DictKey, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for Values<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K: DictKey, V> FusedIterator for Values<'_, K, V> {}
+
+/// Mutable iterator over the values of an [`OptionalDict`], in index order
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K: DictKey, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for ValuesMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+
+impl<K: DictKey, V> FusedIterator for ValuesMut<'_, K, V> {}
+
+/// A view into a single slot of an [`OptionalDict`], from [`OptionalDict::entry`]
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    /// Return the key of this entry
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Insert `default` if the entry is vacant, and return a mutable reference to the value
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Insert the result of `default` if the entry is vacant, and return a mutable reference to the value
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Insert the result of `default` called with the key if the entry is vacant, and return a mutable reference to
+    /// the value
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Call `f` on the value if the entry is occupied
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+
+    /// Insert the default value if the entry is vacant, and return a mutable reference to the value
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+/// A slot of an [`OptionalDict`] with a value
+pub struct OccupiedEntry<'a, K, V> {
+    key: K,
+    /// Always `Some`
+    slot: &'a mut Option<V>,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn get(&self) -> &V {
+        self.slot.as_ref().unwrap()
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.slot.as_mut().unwrap()
+    }
+
+    /// Convert into a mutable reference to the value, with the lifetime of the dict
+    pub fn into_mut(self) -> &'a mut V {
+        self.slot.as_mut().unwrap()
+    }
+
+    /// Set the value, returning the previous one
+    pub fn insert(&mut self, value: V) -> V {
+        self.slot.replace(value).unwrap()
+    }
+
+    /// Remove the value, returning it
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Remove the value, returning the key and the value
+    pub fn remove_entry(self) -> (K, V) {
+        (self.key, self.slot.take().unwrap())
+    }
+}
+
+/// A slot of an [`OptionalDict`] without a value
+pub struct VacantEntry<'a, K, V> {
+    key: K,
+    /// Always `None`
+    slot: &'a mut Option<V>,
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Set the value, returning a mutable reference to it
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.slot.insert(value)
+    }
+}
+
 #[cfg(feature = "serde")]
 mod serde_impl {
     use serde::ser::SerializeMap;
@@ -149,11 +757,9 @@ mod serde_impl {
 
     impl<K: DictKey, V: Serialize> Serialize for OptionalDict<K, V> {
         fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-            let mut map = serializer.serialize_map(Some(self.inner.len()))?;
-            for (index, value) in self.inner.iter().enumerate() {
-                if let Some(value) = value {
-                    map.serialize_entry(K::VARIANTS[index], value)?;
-                }
+            let mut map = serializer.serialize_map(Some(self.len()))?;
+            for (name, value) in self.named_entries() {
+                map.serialize_entry(name, value)?;
             }
             map.end()
         }
@@ -161,12 +767,9 @@ mod serde_impl {
 
     impl<'de, K: DictKey, V: Deserialize<'de>> Deserialize<'de> for OptionalDict<K, V> {
         fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-            let vec = deserializer.deserialize_map(DictVisitor::<K, V>::new())?;
+            let (array, _) = deserializer.deserialize_map(DictVisitor::<K, V>::new())?;
 
-            Ok(Self {
-                inner: vec,
-                phantom: PhantomData,
-            })
+            Ok(Self::from_array(array))
         }
     }
 }
@@ -174,7 +777,7 @@ mod serde_impl {
 #[macro_export]
 macro_rules! optional_dict {
     ($($key:pat => $value:expr),* $(,)?) => {{
-        $crate::OptionalDict::from(|k| {
+        $crate::OptionalDict::from_fn(|k| {
             match k {
                 $($key => Some($value)),* ,
                 _ => None,
@@ -1,83 +1,183 @@
+//! [`RequiredDict`] and its iterators
+
 use std::fmt::{Debug, Display};
 use std::hash::{Hash, Hasher};
+use std::iter::{Enumerate, FusedIterator};
 use std::marker::PhantomData;
-use std::ops::{Index, IndexMut};
-use std::str::FromStr;
+use std::mem::ManuallyDrop;
+use std::ops::{Index, IndexMut, Range};
+use std::{ptr, slice};
 
-use crate::DictKey;
+use crate::dict_key::key_at;
+use crate::{DictArray, DictKey, Fallible};
 
 /// A dictionary that requires all keys to have values
-pub struct RequiredDict<K, V> {
-    inner: Vec<V>,
-    phantom: PhantomData<K>,
+pub struct RequiredDict<K: DictKey, V> {
+    inner: K::Array<V>,
 }
 
-impl<K, V> RequiredDict<K, V> {
+impl<K: DictKey, V> RequiredDict<K, V> {
+    /// Create a new RequiredDict by calling `f` for each key
+    pub fn from_fn<F: FnMut(K) -> V>(mut f: F) -> Self {
+        Self {
+            inner: K::Array::from_fn(|index| f(key_at(index))),
+        }
+    }
+
     pub fn len(&self) -> usize {
-        self.inner.len()
+        K::LENGTH
     }
 
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Iterate over the entries, in index order
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.inner.as_slice().iter().enumerate(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Iterate over the entries with mutable references to the values, in index order
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.inner.as_mut_slice().iter_mut().enumerate(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Iterate over the keys, in index order
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Iterate over the values, in index order
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// Iterate over mutable references to the values, in index order
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut { inner: self.iter_mut() }
+    }
+
+    /// Create a dict with the same keys by calling `f` on each value, in index order
+    pub fn map<U, F: FnMut(V) -> U>(self, mut f: F) -> RequiredDict<K, U> {
+        self.map_with_key(|_, value| f(value))
+    }
+
+    /// Create a dict with the same keys by calling `f` on each entry, in index order
+    pub fn map_with_key<U, F: FnMut(K, V) -> U>(self, mut f: F) -> RequiredDict<K, U> {
+        let mut entries = self.into_iter();
+        // `from_fn` visits the keys in index order, like `entries`
+        RequiredDict::from_fn(|_| {
+            let (key, value) = entries.next().unwrap();
+            f(key, value)
+        })
+    }
+
+    /// Like [`map`](Self::map), but stop at the first `Err` or `None` returned by `f` and return it
+    pub fn try_map<R: Fallible, F: FnMut(V) -> R>(self, mut f: F) -> R::Wrapped<RequiredDict<K, R::Output>> {
+        let mut residual = None;
+        let mut values = self.into_iter().map(|(_, value)| value);
+        let mut slots: K::Array<Option<R::Output>> = K::Array::from_fn(|_| {
+            if residual.is_some() {
+                return None;
+            }
+            f(values.next().unwrap())
+                .branch()
+                .map_err(|err| residual = Some(err))
+                .ok()
+        });
+        R::wrap(match residual {
+            Some(residual) => Err(residual),
+            None => Ok(RequiredDict {
+                inner: K::Array::from_fn(|index| slots.as_mut_slice()[index].take().unwrap()),
+            }),
+        })
+    }
+
+    /// Pair up the values of both dicts by key
+    pub fn zip<U>(self, other: RequiredDict<K, U>) -> RequiredDict<K, (V, U)> {
+        self.zip_with(other, |value, other| (value, other))
+    }
+
+    /// Combine the values of both dicts by key with `f`, in index order
+    pub fn zip_with<U, W, F: FnMut(V, U) -> W>(self, other: RequiredDict<K, U>, mut f: F) -> RequiredDict<K, W> {
+        let mut others = other.into_iter();
+        self.map(|value| f(value, others.next().unwrap().1))
+    }
+
+    /// Iterate over the entries of `keys`, e.g. a group generated by `#[enum_dict(group = "...")]`
+    pub fn iter_group<'a>(&'a self, keys: &'a [K]) -> impl Iterator<Item = (K, &'a V)>
+    where
+        K: Clone,
+    {
+        keys.iter().map(|key| (key.clone(), &self[key.clone()]))
+    }
+
+    /// Iterate over the entries by name, fetching the names once rather than once per entry
+    pub(crate) fn named_entries(&self) -> impl Iterator<Item = (&'static str, &V)> {
+        K::variants().iter().copied().zip(self.inner.as_slice())
+    }
 }
 
 impl<K, V, F> From<F> for RequiredDict<K, V>
 where
-    K: DictKey + FromStr,
-    K::Err: Debug,
+    K: DictKey,
     F: Fn(K) -> V,
 {
     fn from(f: F) -> Self {
-        Self {
-            // SAFETY: K::VARIANTS are all valid keys
-            inner: K::VARIANTS.iter().map(|s| f(s.parse().unwrap())).collect(),
-            phantom: PhantomData,
-        }
+        Self::from_fn(f)
     }
 }
 
 impl<K: DictKey, V: Default> Default for RequiredDict<K, V> {
     fn default() -> Self {
         Self {
-            inner: K::VARIANTS.iter().map(|_| V::default()).collect(),
-            phantom: PhantomData,
+            inner: K::Array::from_fn(|_| V::default()),
         }
     }
 }
 
-impl<K, V: Clone> Clone for RequiredDict<K, V> {
+impl<K: DictKey, V> Clone for RequiredDict<K, V>
+where
+    K::Array<V>: Clone,
+{
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
-            phantom: PhantomData,
         }
     }
 }
 
-impl<K, V: PartialEq> PartialEq for RequiredDict<K, V> {
+impl<K: DictKey, V> Copy for RequiredDict<K, V> where K::Array<V>: Copy {}
+
+impl<K: DictKey, V: PartialEq> PartialEq for RequiredDict<K, V> {
     fn eq(&self, other: &Self) -> bool {
-        self.inner == other.inner
+        self.inner.as_slice() == other.inner.as_slice()
     }
 }
 
-impl<K, V: Eq> Eq for RequiredDict<K, V> {}
+impl<K: DictKey, V: Eq> Eq for RequiredDict<K, V> {}
 
-impl<K, V: PartialOrd> PartialOrd for RequiredDict<K, V> {
+impl<K: DictKey, V: PartialOrd> PartialOrd for RequiredDict<K, V> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.inner.partial_cmp(&other.inner)
+        self.inner.as_slice().partial_cmp(other.inner.as_slice())
     }
 }
 
-impl<K, V: Ord> Ord for RequiredDict<K, V> {
+impl<K: DictKey, V: Ord> Ord for RequiredDict<K, V> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.inner.cmp(&other.inner)
+        self.inner.as_slice().cmp(other.inner.as_slice())
     }
 }
 
-impl<K, V: Hash> Hash for RequiredDict<K, V> {
+impl<K: DictKey, V: Hash> Hash for RequiredDict<K, V> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.inner.hash(state);
+        self.inner.as_slice().hash(state);
     }
 }
 
@@ -85,57 +185,285 @@ impl<K: DictKey, V> Index<K> for RequiredDict<K, V> {
     type Output = V;
 
     fn index(&self, key: K) -> &Self::Output {
-        &self.inner[key.variant_index()]
+        &self.inner.as_slice()[key.variant_index()]
     }
 }
 
 impl<K: DictKey, V> IndexMut<K> for RequiredDict<K, V> {
     fn index_mut(&mut self, key: K) -> &mut Self::Output {
-        &mut self.inner[key.variant_index()]
+        &mut self.inner.as_mut_slice()[key.variant_index()]
     }
 }
 
 impl<K: DictKey, V: Debug> Debug for RequiredDict<K, V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_map()
-            .entries(
-                self.inner
-                    .iter()
-                    .enumerate()
-                    .map(|(index, value)| (K::VARIANTS[index], value)),
-            )
-            .finish()
+        f.debug_map().entries(self.named_entries()).finish()
     }
 }
 
 impl<K: DictKey, V: Display> Display for RequiredDict<K, V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{{")?;
-        let mut is_first = true;
-        for (index, value) in self.inner.iter().enumerate() {
-            if is_first {
+        for (index, (name, value)) in self.named_entries().enumerate() {
+            if index > 0 {
                 write!(f, ", ")?;
             }
-            write!(f, "{}: {}", K::VARIANTS[index], value)?;
-            is_first = false;
+            write!(f, "{name}: {value}")?;
         }
         write!(f, "}}")
     }
 }
 
+impl<'a, K: DictKey, V> IntoIterator for &'a RequiredDict<K, V> {
+    type Item = (K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K: DictKey, V> IntoIterator for &'a mut RequiredDict<K, V> {
+    type Item = (K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K: DictKey, V> IntoIterator for RequiredDict<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: ManuallyDrop::new(self.inner),
+            alive: 0..K::LENGTH,
+        }
+    }
+}
+
+/// Iterator over the entries of a [`RequiredDict`], in index order
+pub struct Iter<'a, K, V> {
+    inner: Enumerate<slice::Iter<'a, V>>,
+    marker: PhantomData<fn() -> K>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K: DictKey, V> Iterator for Iter<'a, K, V> {
+    type Item = (K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, value)| (key_at(index), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, value)| (key_at(index), value))
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K: DictKey, V> FusedIterator for Iter<'_, K, V> {}
+
+/// Mutable iterator over the entries of a [`RequiredDict`], in index order
+pub struct IterMut<'a, K, V> {
+    inner: Enumerate<slice::IterMut<'a, V>>,
+    marker: PhantomData<fn() -> K>,
+}
+
+impl<'a, K: DictKey, V> Iterator for IterMut<'a, K, V> {
+    type Item = (K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, value)| (key_at(index), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, value)| (key_at(index), value))
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K: DictKey, V> FusedIterator for IterMut<'_, K, V> {}
+
+/// Owning iterator over the entries of a [`RequiredDict`], in index order
+pub struct IntoIter<K: DictKey, V> {
+    inner: ManuallyDrop<K::Array<V>>,
+    /// Indexes of the values not yet moved out
+    alive: Range<usize>,
+}
+
+impl<K: DictKey, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.alive.next()?;
+        // SAFETY: `index` was alive, and is no longer, so the value is read exactly once
+        let value = unsafe { ptr::read(&self.inner.as_slice()[index]) };
+        Some((key_at(index), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.alive.size_hint()
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.alive.next_back()?;
+        // SAFETY: see `next`
+        let value = unsafe { ptr::read(&self.inner.as_slice()[index]) };
+        Some((key_at(index), value))
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K: DictKey, V> FusedIterator for IntoIter<K, V> {}
+
+impl<K: DictKey, V> Drop for IntoIter<K, V> {
+    fn drop(&mut self) {
+        let alive = self.alive.clone();
+        // SAFETY: the values at alive indexes have not been moved out
+        unsafe { ptr::drop_in_place(&mut self.inner.as_mut_slice()[alive]) }
+    }
+}
+
+/// Iterator over the keys of a [`RequiredDict`], in index order
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: DictKey, V> Iterator for Keys<'_, K, V> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for Keys<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K: DictKey, V> FusedIterator for Keys<'_, K, V> {}
+
+/// Iterator over the values of a [`RequiredDict`], in index order
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K: DictKey, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for Values<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K: DictKey, V> FusedIterator for Values<'_, K, V> {}
+
+/// Mutable iterator over the values of a [`RequiredDict`], in index order
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K: DictKey, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K: DictKey, V> DoubleEndedIterator for ValuesMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K: DictKey, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+
+impl<K: DictKey, V> FusedIterator for ValuesMut<'_, K, V> {}
+
 #[cfg(feature = "serde")]
 mod serde_impl {
+    use serde::de::IntoDeserializer;
     use serde::ser::SerializeMap;
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
     use super::*;
+    use crate::MissingKeysError;
     use crate::dict_key::DictVisitor;
 
     impl<K: DictKey, V: Serialize> Serialize for RequiredDict<K, V> {
         fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-            let mut map = serializer.serialize_map(Some(self.inner.len()))?;
-            for (index, value) in self.inner.iter().enumerate() {
-                map.serialize_entry(K::VARIANTS[index], value)?;
+            let mut map = serializer.serialize_map(Some(self.len()))?;
+            for (name, value) in self.named_entries() {
+                map.serialize_entry(name, value)?;
             }
             map.end()
         }
@@ -143,26 +471,28 @@ mod serde_impl {
 
     impl<'de, K: DictKey, V: Deserialize<'de>> Deserialize<'de> for RequiredDict<K, V> {
         fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-            let vec = deserializer.deserialize_map(DictVisitor::<K, V>::new())?;
+            let (mut array, _) = deserializer.deserialize_map(DictVisitor::<K, V>::new())?;
 
-            // Check for missing keys
+            // Check for missing keys. The catch-all key is not required: without unknown keys, its value is
+            // deserialized from unit, which gives `None` for an `Option` value.
+            let other_index = K::other().map(K::variant_index);
             let mut missing_keys = vec![];
-            for (index, &name) in K::VARIANTS.iter().enumerate() {
-                if vec[index].is_none() {
+            for (index, &name) in K::variants().iter().enumerate() {
+                let slot = &mut array.as_mut_slice()[index];
+                if slot.is_none() && Some(index) == other_index {
+                    *slot = V::deserialize(IntoDeserializer::<D::Error>::into_deserializer(())).ok();
+                }
+                if slot.is_none() {
                     missing_keys.push(name);
                 }
             }
             if !missing_keys.is_empty() {
-                return Err(serde::de::Error::custom(format!(
-                    "Missing keys: {}",
-                    missing_keys.join(", ")
-                )));
+                return Err(serde::de::Error::custom(MissingKeysError::new(missing_keys)));
             }
 
             Ok(Self {
                 // SAFETY: checked for missing keys above
-                inner: vec.into_iter().map(Option::unwrap).collect(),
-                phantom: PhantomData,
+                inner: K::Array::from_fn(|index| array.as_mut_slice()[index].take().unwrap()),
             })
         }
     }
@@ -171,7 +501,7 @@ mod serde_impl {
 #[macro_export]
 macro_rules! required_dict {
     ($($key:pat => $value:expr),* $(,)?) => {{
-        $crate::RequiredDict::from(|k| {
+        $crate::RequiredDict::from_fn(|k| {
             match k { $($key => $value),* }
         })
     }};
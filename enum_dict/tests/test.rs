@@ -1,7 +1,10 @@
-use enum_dict::{DictKey, OptionalDict, RequiredDict};
+use enum_dict::{
+    Bounded, DictKey, OpenDict, OptionalDict, RequiredDict, define_dict_key, dict_keys_from_file, impl_dict_key,
+    optional_dict, required_dict,
+};
 use serde::{Deserialize, Serialize};
 
-#[derive(DictKey)]
+#[derive(Debug, Clone, Copy, PartialEq, DictKey)]
 enum Key {
     A,
     B,
@@ -53,3 +56,784 @@ fn test_validate() {
     let err = serde_json::from_str::<Data>(json).unwrap_err();
     assert_eq!(err.to_string(), "Missing keys: A, B at line 2 column 22");
 }
+
+#[test]
+fn test_array_storage() {
+    assert_eq!(Key::LENGTH, 2);
+    assert_eq!(size_of::<RequiredDict<Key, u32>>(), size_of::<[u32; 2]>());
+    assert_eq!(size_of::<OptionalDict<Key, u32>>(), size_of::<[Option<u32>; 2]>());
+
+    let required: RequiredDict<Key, u32> = required_dict! {
+        Key::A => 1,
+        Key::B => 2,
+    };
+    let optional: OptionalDict<Key, u32> = optional_dict! {
+        Key::B => 3,
+    };
+
+    // both dicts are `Copy` since `u32` is
+    let (mut required2, optional2) = (required, optional);
+    required2[Key::A] = 4;
+    assert_eq!(required[Key::A], 1);
+    assert_eq!(required2[Key::A], 4);
+    assert_eq!(optional, optional2);
+}
+
+#[test]
+fn test_variant_index() {
+    assert_eq!(Key::all(), &[Key::A, Key::B]);
+    for (index, &key) in Key::all().iter().enumerate() {
+        assert_eq!(key.variant_index(), index);
+        assert_eq!(Key::from_variant_index(index), Some(key));
+    }
+    assert_eq!(Key::from_variant_index(2), None);
+}
+
+#[derive(Clone, Copy)]
+enum Manual {
+    X,
+    Y,
+}
+
+impl DictKey for Manual {
+    const LENGTH: usize = 2;
+
+    type Array<V> = [V; 2];
+
+    fn variants() -> &'static [&'static str] {
+        &["x", "y"]
+    }
+
+    fn all() -> &'static [Self] {
+        &[Manual::X, Manual::Y]
+    }
+
+    fn variant_index(self) -> usize {
+        self as usize
+    }
+
+    fn from_variant_index(index: usize) -> Option<Self> {
+        Self::all().get(index).copied()
+    }
+}
+
+#[test]
+fn test_from_fn() {
+    let required = RequiredDict::from_fn(|key: Manual| key.variant_index() * 10);
+    assert_eq!(required[Manual::X], 0);
+    assert_eq!(required[Manual::Y], 10);
+
+    let required: RequiredDict<Manual, &str> = required_dict! {
+        Manual::X => "x",
+        Manual::Y => "y",
+    };
+    assert_eq!(required[Manual::Y], "y");
+
+    let optional: OptionalDict<Manual, u32> = optional_dict! {
+        Manual::Y => 1,
+    };
+    assert_eq!(optional[Manual::X], None);
+    assert_eq!(optional[Manual::Y], Some(1));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DictKey)]
+#[repr(u16)]
+enum Code {
+    Ok = 200,
+    NotFound = 404,
+    Internal = 500,
+}
+
+#[test]
+fn test_discriminants() {
+    assert_eq!(Code::NotFound.variant_index(), 1);
+    assert_eq!(Code::from_variant_index(2), Some(Code::Internal));
+    assert_eq!(Code::NotFound as u16, 404);
+
+    let mut dict = RequiredDict::from_fn(|code: Code| code as u16);
+    dict[Code::Internal] += 1;
+    assert_eq!(dict[Code::Ok], 200);
+    assert_eq!(dict[Code::NotFound], 404);
+    assert_eq!(dict[Code::Internal], 501);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DictKey)]
+enum Input {
+    Key(Key),
+    #[enum_dict(rename = "mouse")]
+    Mouse(Code),
+    Idle,
+}
+
+#[test]
+fn test_nested() {
+    assert_eq!(Input::LENGTH, 6);
+    assert_eq!(
+        Input::variants(),
+        &["Key.A", "Key.B", "mouse.Ok", "mouse.NotFound", "mouse.Internal", "Idle"]
+    );
+    for (index, &key) in Input::all().iter().enumerate() {
+        assert_eq!(key.variant_index(), index);
+        assert_eq!(Input::from_variant_index(index), Some(key));
+        assert_eq!(Input::variants()[index].parse(), Ok(key));
+    }
+    assert_eq!(Input::from_variant_index(6), None);
+    assert!("Key.C".parse::<Input>().is_err());
+    assert!("Idle.A".parse::<Input>().is_err());
+
+    let dict: OptionalDict<Input, u32> = optional_dict! {
+        Input::Key(Key::B) => 1,
+        Input::Idle => 2,
+    };
+    let json = serde_json::to_string(&dict).unwrap();
+    assert_eq!(json, r#"{"Key.B":1,"Idle":2}"#);
+    assert_eq!(serde_json::from_str::<OptionalDict<Input, u32>>(&json).unwrap(), dict);
+}
+
+#[test]
+fn test_composite_keys() {
+    assert_eq!(bool::variants(), &["false", "true"]);
+    assert_eq!(<Option<Key>>::variants(), &["None", "Some.A", "Some.B"]);
+    assert_eq!(<Bounded<3>>::variants(), &["0", "1", "2"]);
+    assert_eq!(<(Key, bool)>::variants(), &["A.false", "A.true", "B.false", "B.true"]);
+    assert_eq!(<(Key, bool, Key)>::LENGTH, 8);
+    assert_eq!(<(Key, bool, Key)>::variants()[5], "B.false.B");
+
+    fn check<K: DictKey + Copy + PartialEq + std::fmt::Debug>() {
+        assert_eq!(K::variants().len(), K::LENGTH);
+        assert_eq!(K::all().len(), K::LENGTH);
+        for (index, &key) in K::all().iter().enumerate() {
+            assert_eq!(key.variant_index(), index);
+            assert_eq!(K::from_variant_index(index), Some(key));
+        }
+        assert_eq!(K::from_variant_index(K::LENGTH), None);
+    }
+    check::<bool>();
+    check::<Option<Key>>();
+    check::<Bounded<3>>();
+    check::<(Key, Code)>();
+    check::<(Key, Option<bool>, Bounded<2>)>();
+
+    let mut dict = RequiredDict::from_fn(|(key, flag): (Key, Option<bool>)| (key.variant_index(), flag));
+    dict[(Key::B, None)].0 = 10;
+    assert_eq!(dict[(Key::A, Some(true))], (0, Some(true)));
+    assert_eq!(dict[(Key::B, None)], (10, None));
+
+    let dict: OptionalDict<(Key, Option<bool>), u32> = optional_dict! {
+        (Key::B, Some(false)) => 1,
+    };
+    let json = serde_json::to_string(&dict).unwrap();
+    assert_eq!(json, r#"{"B.Some.false":1}"#);
+    assert_eq!(
+        serde_json::from_str::<OptionalDict<(Key, Option<bool>), u32>>(&json).unwrap(),
+        dict
+    );
+}
+
+mod generated {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Status {
+        Active = 1,
+        Disabled = 3,
+        Deleted = 4,
+    }
+}
+
+impl_dict_key!(generated::Status {
+    Active = "active",
+    Disabled,
+    Deleted = "deleted",
+});
+
+impl_dict_key!(
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Alignment(std::fmt::Alignment) { Left, Center, Right }
+);
+
+#[test]
+fn test_impl_dict_key() {
+    use generated::Status;
+
+    assert_eq!(Status::variants(), &["active", "Disabled", "deleted"]);
+    assert_eq!(Status::Deleted.variant_index(), 2);
+    assert_eq!(Status::from_variant_index(1), Some(Status::Disabled));
+    assert_eq!(Status::from_variant_index(3), None);
+
+    assert_eq!(Alignment::LENGTH, 3);
+    assert_eq!(Alignment(std::fmt::Alignment::Center).variant_index(), 1);
+    assert_eq!(Alignment::all()[2], Alignment(std::fmt::Alignment::Right));
+
+    let dict: RequiredDict<std::cmp::Ordering, &str> = required_dict! {
+        std::cmp::Ordering::Less => "<",
+        std::cmp::Ordering::Equal => "=",
+        std::cmp::Ordering::Greater => ">",
+    };
+    assert_eq!(dict[1.cmp(&2)], "<");
+    assert_eq!(
+        serde_json::to_string(&dict).unwrap(),
+        r#"{"Less":"<","Equal":"=","Greater":">"}"#
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DictKey)]
+#[enum_dict(rename_all = "kebab-case")]
+enum Locale {
+    #[enum_dict(alias = "en", alias = "en-us")]
+    English,
+    #[enum_dict(rename = "zh-hans", alias = "zh")]
+    Chinese,
+}
+
+#[test]
+fn test_alias() {
+    assert_eq!(Locale::variants(), &["english", "zh-hans"]);
+    assert_eq!("english".parse(), Ok(Locale::English));
+    assert_eq!("en-us".parse(), Ok(Locale::English));
+    assert_eq!("zh".parse(), Ok(Locale::Chinese));
+    assert!("Chinese".parse::<Locale>().is_err());
+    assert_eq!(
+        <(Locale, Option<Locale>)>::from_name("en.Some.zh"),
+        Some((Locale::English, Some(Locale::Chinese)))
+    );
+
+    let dict: RequiredDict<Locale, u32> = serde_json::from_str(r#"{"en":1,"zh":2}"#).unwrap();
+    assert_eq!(serde_json::to_string(&dict).unwrap(), r#"{"english":1,"zh-hans":2}"#);
+
+    let dict: OptionalDict<Locale, u32> = serde_json::from_str(r#"{"en-us":1}"#).unwrap();
+    assert_eq!(dict[Locale::English], Some(1));
+}
+
+#[derive(Debug, Clone, PartialEq, DictKey)]
+#[enum_dict(display)]
+enum Level {
+    #[enum_dict(skip)]
+    Unknown(String),
+    Low,
+    #[enum_dict(skip)]
+    __Count,
+    High,
+}
+
+#[test]
+fn test_skip() {
+    assert_eq!(Level::LENGTH, 2);
+    assert_eq!(Level::variants(), &["Low", "High"]);
+    assert_eq!(Level::all(), &[Level::Low, Level::High]);
+    assert_eq!(Level::High.variant_index(), 1);
+    assert_eq!(Level::from_variant_index(1), Some(Level::High));
+    assert!("Unknown".parse::<Level>().is_err());
+    assert!("__Count".parse::<Level>().is_err());
+    assert_eq!(Level::Low.to_string(), "Low");
+    assert_eq!(Level::Unknown("?".into()).to_string(), "Unknown");
+}
+
+#[test]
+#[should_panic(expected = "`Level::Unknown` is skipped and cannot be used as a dict key")]
+fn test_skip_index() {
+    let dict = RequiredDict::from_fn(|level: Level| level.variant_index());
+    let _ = dict[Level::Unknown("?".into())];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DictKey)]
+#[enum_dict(rename_all = "snake_case", case_insensitive, normalize = "strip_separators")]
+enum Region {
+    NorthAmerica,
+    #[enum_dict(alias = "EU")]
+    Europe,
+    Local(Key),
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(Region::variants(), &["north_america", "europe", "local.A", "local.B"]);
+    assert_eq!("north_america".parse(), Ok(Region::NorthAmerica));
+    assert_eq!("North-America".parse(), Ok(Region::NorthAmerica));
+    assert_eq!("NORTH AMERICA".parse(), Ok(Region::NorthAmerica));
+    assert_eq!("eu".parse(), Ok(Region::Europe));
+    // nested keys keep their own matching rules
+    assert_eq!("LOCAL.B".parse(), Ok(Region::Local(Key::B)));
+    assert!("local.b".parse::<Region>().is_err());
+    assert!("north.america".parse::<Region>().is_err());
+
+    let dict: OptionalDict<Region, u32> = serde_json::from_str(r#"{"NorthAmerica":1,"Eu":2}"#).unwrap();
+    assert_eq!(dict[Region::NorthAmerica], Some(1));
+    assert_eq!(dict[Region::Europe], Some(2));
+}
+
+mod facade {
+    pub use enum_dict as dict;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DictKey)]
+#[enum_dict(crate = "crate::facade::dict", case_insensitive)]
+enum Facade {
+    Inner(Key),
+    Outer,
+}
+
+#[test]
+fn test_crate_path() {
+    assert_eq!(Facade::variants(), &["Inner.A", "Inner.B", "Outer"]);
+    assert_eq!("outer".parse(), Ok(Facade::Outer));
+    assert_eq!("inner.B".parse(), Ok(Facade::Inner(Key::B)));
+}
+
+mod prelude_shadowed {
+    use enum_dict::DictKey;
+
+    // variants named like the prelude's `Option` must not break the generated code
+    #[allow(unused_imports)]
+    use self::Mode::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, DictKey)]
+    pub enum Mode {
+        #[enum_dict(group = "off")]
+        None,
+        Some,
+        Nested(super::Key),
+        #[enum_dict(other)]
+        Unknown,
+    }
+}
+
+#[test]
+fn test_prelude_shadowed() {
+    use prelude_shadowed::Mode;
+
+    assert_eq!("None".parse(), Ok(Mode::None));
+    assert_eq!("Nested.B".parse(), Ok(Mode::Nested(Key::B)));
+    assert_eq!("x".parse(), Ok(Mode::Unknown));
+    assert_eq!(Mode::from_variant_index(1), Some(Mode::Some));
+    assert_eq!(Mode::group("off"), Some(&[Mode::None][..]));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DictKey)]
+#[enum_dict(no_from_str, display, rename_all = "lowercase")]
+enum Shape {
+    Circle,
+    Square(Key),
+}
+
+impl std::str::FromStr for Shape {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_name(&s.to_lowercase()).ok_or(())
+    }
+}
+
+#[test]
+fn test_name() {
+    assert_eq!(Key::B.name(), "B");
+    assert_eq!(Shape::Square(Key::A).name(), "square.A");
+    assert_eq!(Some((Key::A, true)).name(), "Some.A.true");
+    assert_eq!(Shape::Circle.to_string(), "circle");
+    assert_eq!(Shape::Square(Key::B).to_string(), "square.B");
+    assert_eq!("CIRCLE".parse(), Ok(Shape::Circle));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DictKey, Deserialize, Serialize)]
+#[enum_dict(serde)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum Protocol {
+    HTTPServer,
+    #[serde(rename(serialize = "ws", deserialize = "websocket"), alias = "socket")]
+    WebSocket,
+    #[serde(rename = "raw")]
+    #[enum_dict(rename = "tcp")]
+    Tcp,
+}
+
+#[test]
+fn test_serde_names() {
+    assert_eq!(Protocol::variants(), &["h_t_t_p_server", "ws", "tcp"]);
+    for &protocol in &Protocol::all()[..2] {
+        assert_eq!(
+            serde_json::to_string(&protocol).unwrap(),
+            format!("\"{}\"", protocol.name())
+        );
+    }
+    assert_eq!("websocket".parse(), Ok(Protocol::WebSocket));
+    assert_eq!("socket".parse(), Ok(Protocol::WebSocket));
+    assert!("raw".parse::<Protocol>().is_err());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DictKey)]
+enum Version {
+    V1,
+    V2,
+    #[enum_dict(other)]
+    Unknown,
+}
+
+#[test]
+fn test_other() {
+    assert_eq!(Version::other(), Some(Version::Unknown));
+    assert_eq!("V2".parse(), Ok(Version::V2));
+    assert_eq!("V9".parse(), Ok(Version::Unknown));
+    assert_eq!(Key::other(), None);
+
+    let dict: OptionalDict<Version, u32> = serde_json::from_str(r#"{"V1":1,"V9":9}"#).unwrap();
+    assert_eq!(dict[Version::Unknown], Some(9));
+    let dict: OptionalDict<Version, u32> = serde_json::from_str(r#"{"V1":1,"V8":8,"V9":9}"#).unwrap();
+    assert_eq!(dict[Version::Unknown], Some(9));
+
+    let dict: RequiredDict<Version, Option<u32>> = serde_json::from_str(r#"{"V1":1,"V2":2}"#).unwrap();
+    assert_eq!(dict[Version::Unknown], None);
+    let result = serde_json::from_str::<RequiredDict<Version, u32>>(r#"{"V1":1,"V2":2}"#);
+    assert!(result.unwrap_err().to_string().contains("Missing keys: Unknown"));
+    let result = serde_json::from_str::<RequiredDict<Version, u32>>(r#"{"V2":2,"V9":9}"#);
+    assert!(result.unwrap_err().to_string().contains("Missing keys: V1"));
+
+    let json = r#"{"V1":1,"V9":9,"Unknown":0,"V8":8}"#;
+    let dict: OpenDict<Version, u32> = serde_json::from_str(json).unwrap();
+    assert_eq!(dict[Version::V1], Some(1));
+    assert_eq!(dict[Version::Unknown], None);
+    assert_eq!(
+        dict.unknown(),
+        &[("V9".into(), 9), ("Unknown".into(), 0), ("V8".into(), 8)]
+    );
+    assert_eq!(serde_json::to_string(&dict).unwrap(), json);
+
+    let dict: OpenDict<Key, u32> = serde_json::from_str(r#"{"B":2,"C":3}"#).unwrap();
+    assert_eq!(format!("{dict:?}"), r#"{"B": 2, "C": 3}"#);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DictKey)]
+enum Color {
+    #[enum_dict(group = "primary", group = "warm")]
+    Red,
+    #[enum_dict(group = "primary", group = "mixed-bag")]
+    Blue,
+    #[enum_dict(group = "warm")]
+    Orange,
+    #[enum_dict(group = "mixed-bag")]
+    Custom(Key),
+}
+
+#[test]
+fn test_groups() {
+    assert_eq!(Color::primary(), &[Color::Red, Color::Blue]);
+    assert_eq!(Color::warm(), &[Color::Red, Color::Orange]);
+    assert_eq!(
+        Color::mixed_bag(),
+        &[Color::Blue, Color::Custom(Key::A), Color::Custom(Key::B)]
+    );
+    assert_eq!(Color::groups(), &["primary", "warm", "mixed-bag"]);
+    assert_eq!(Color::group("warm"), Some(Color::warm()));
+    assert_eq!(Color::group("cool"), None);
+    assert_eq!(Key::groups(), &[] as &[&str]);
+
+    let required = RequiredDict::from_fn(|color: Color| color.variant_index());
+    let values: Vec<_> = required
+        .iter_group(Color::mixed_bag())
+        .map(|(_, &value)| value)
+        .collect();
+    assert_eq!(values, [1, 3, 4]);
+
+    let optional: OptionalDict<Color, u32> = optional_dict! { Color::Red => 1, Color::Orange => 2 };
+    let entries: Vec<_> = optional.iter_group(Color::primary()).collect();
+    assert_eq!(entries, [(Color::Red, &1)]);
+    assert_eq!(optional.validate_group(Color::warm()), Ok(()));
+    let err = optional.validate_group(Color::mixed_bag()).unwrap_err();
+    assert_eq!(err.keys(), &["Blue", "Custom.A", "Custom.B"]);
+    assert_eq!(err.to_string(), "Missing keys: Blue, Custom.A, Custom.B");
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, DictKey)]
+enum Metric {
+    /// Requests per second
+    #[enum_dict(meta(unit = "1/s", chart = "line"))]
+    Throughput,
+    /// Time to first byte
+    ///
+    /// Measured at the load balancer.
+    #[enum_dict(meta(unit = "ms"))]
+    Latency,
+    Errors(Key),
+}
+
+#[test]
+fn test_docs_and_metadata() {
+    assert_eq!(Metric::Throughput.doc(), "Requests per second");
+    assert_eq!(
+        Metric::docs(),
+        &[
+            "Requests per second",
+            "Time to first byte\n\nMeasured at the load balancer.",
+            "",
+            ""
+        ]
+    );
+    assert_eq!(Metric::Latency.meta("unit"), Some("ms"));
+    assert_eq!(Metric::Throughput.metadata(), &[("unit", "1/s"), ("chart", "line")]);
+    assert_eq!(Metric::Latency.meta("chart"), None);
+    assert_eq!(Metric::Errors(Key::A).metadata(), &[]);
+    assert_eq!(Key::docs(), &["", ""]);
+}
+
+define_dict_key! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Fruit {
+        /// Red or green
+        Apple = "apple",
+        Banana,
+    }
+}
+
+#[test]
+fn test_define_dict_key() {
+    assert_eq!(Fruit::LENGTH, 2);
+    assert_eq!(Fruit::variants(), &["apple", "Banana"]);
+    assert_eq!(Fruit::all(), &[Fruit::Apple, Fruit::Banana]);
+    assert_eq!(Fruit::Banana.variant_index(), 1);
+    assert_eq!(Fruit::Apple.doc(), "Red or green");
+    assert_eq!(Fruit::docs(), &["Red or green", ""]);
+    assert_eq!("apple".parse(), Ok(Fruit::Apple));
+    assert_eq!("Apple".parse::<Fruit>().unwrap_err().suggestions(), &["apple"]);
+
+    let dict: RequiredDict<Fruit, u32> = serde_json::from_str(r#"{"apple":1,"Banana":2}"#).unwrap();
+    assert_eq!(dict[Fruit::Apple], 1);
+}
+
+dict_keys_from_file!(
+    "tests/data/locales.toml",
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum FileLocale
+);
+
+dict_keys_from_file!(
+    "tests/data/flags.json",
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[enum_dict(case_insensitive)]
+    enum Flag,
+);
+
+#[test]
+fn test_keys_from_file() {
+    assert_eq!(FileLocale::variants(), &["en-US", "zh-Hans", "ja"]);
+    assert_eq!(FileLocale::ZhHans.doc(), "Chinese (Simplified)");
+    assert_eq!(FileLocale::Ja.doc(), "");
+    assert_eq!("en-US".parse(), Ok(FileLocale::EnUs));
+
+    assert_eq!(Flag::variants(), &["dark_mode", "beta-search", "newCheckout"]);
+    assert_eq!("NEWCHECKOUT".parse(), Ok(Flag::NewCheckout));
+    assert_eq!(Flag::BetaSearch.variant_index(), 1);
+}
+
+#[test]
+fn test_iter() {
+    let mut required = RequiredDict::from_fn(|color: Color| color.variant_index() as u32);
+    let entries: Vec<_> = required.iter().collect();
+    assert_eq!(entries[1], (Color::Blue, &1));
+    assert_eq!(required.iter().len(), 5);
+    assert_eq!(required.keys().next_back(), Some(Color::Custom(Key::B)));
+    assert_eq!(required.values().rev().copied().collect::<Vec<_>>(), [4, 3, 2, 1, 0]);
+    for (color, value) in &mut required {
+        if color == Color::Red {
+            *value = 10;
+        }
+    }
+    required.values_mut().for_each(|value| *value += 1);
+    assert_eq!(required.values().sum::<u32>(), 25);
+    assert_eq!(required.into_iter().nth(2), Some((Color::Orange, 3)));
+
+    let mut optional: OptionalDict<Color, String> = optional_dict! {
+        Color::Blue => "blue".to_string(),
+        Color::Custom(Key::A) => "custom".to_string(),
+    };
+    assert_eq!(
+        optional.keys().collect::<Vec<_>>(),
+        [Color::Blue, Color::Custom(Key::A)]
+    );
+    assert_eq!(
+        optional.iter().next_back(),
+        Some((Color::Custom(Key::A), &"custom".to_string()))
+    );
+    for value in optional.values_mut() {
+        value.push('!');
+    }
+    assert_eq!(optional.to_string(), "{Blue: blue!, Custom.A: custom!}");
+    assert_eq!(format!("{optional:?}"), r#"{"Blue": "blue!", "Custom.A": "custom!"}"#);
+
+    let mut into_iter = optional.into_iter();
+    assert_eq!(
+        into_iter.next_back(),
+        Some((Color::Custom(Key::A), "custom!".to_string()))
+    );
+    assert_eq!(into_iter.next(), Some((Color::Blue, "blue!".to_string())));
+    assert_eq!(into_iter.next(), None);
+
+    // dropping a partially consumed iterator drops the remaining values once
+    let names = RequiredDict::from_fn(|key: Key| format!("{key:?}"));
+    let mut into_iter = names.into_iter();
+    assert_eq!(into_iter.next(), Some((Key::A, "A".to_string())));
+    drop(into_iter);
+}
+
+#[test]
+fn test_map_methods() {
+    let mut dict: OptionalDict<Color, u32> = [(Color::Red, 1), (Color::Blue, 2)].into_iter().collect();
+    assert_eq!(dict.insert(Color::Red, 3), Some(1));
+    assert_eq!(dict.insert(Color::Orange, 4), None);
+    assert_eq!(dict.get(Color::Red), Some(&3));
+    assert_eq!(dict.get(Color::Custom(Key::A)), None);
+    *dict.get_mut(Color::Blue).unwrap() += 10;
+    assert_eq!(dict.get_key_value(Color::Blue), Some((Color::Blue, &12)));
+    assert!(dict.contains_key(Color::Orange));
+    assert_eq!(dict.remove(Color::Orange), Some(4));
+    assert_eq!(dict.remove(Color::Orange), None);
+    assert!(!dict.contains_key(Color::Orange));
+    assert_eq!(dict.remove_entry(Color::Red), Some((Color::Red, 3)));
+
+    dict.extend([(Color::Red, 5), (Color::Custom(Key::B), 6)]);
+    dict.retain(|&color, value| {
+        *value += 1;
+        color != Color::Blue
+    });
+    assert_eq!(
+        dict.drain().collect::<Vec<_>>(),
+        [(Color::Red, 6), (Color::Custom(Key::B), 7)]
+    );
+    assert!(dict.is_empty());
+
+    dict.insert(Color::Red, 1);
+    dict.clear();
+    assert_eq!(dict.len(), 0);
+}
+
+#[test]
+fn test_entry() {
+    use enum_dict::optional_dict::Entry;
+
+    let mut dict: OptionalDict<Color, Vec<u32>> = OptionalDict::new();
+    for (color, value) in [(Color::Red, 1), (Color::Blue, 2), (Color::Red, 3)] {
+        dict.entry(color).or_default().push(value);
+    }
+    assert_eq!(dict.get(Color::Red), Some(&vec![1, 3]));
+
+    dict.entry(Color::Blue)
+        .and_modify(|values| values.clear())
+        .or_insert(vec![0]);
+    dict.entry(Color::Orange)
+        .and_modify(|values| values.clear())
+        .or_insert_with(|| vec![0]);
+    assert_eq!(dict[Color::Blue], Some(vec![]));
+    assert_eq!(dict[Color::Orange], Some(vec![0]));
+
+    let values = dict
+        .entry(Color::Custom(Key::B))
+        .or_insert_with_key(|color| vec![color.variant_index() as u32]);
+    assert_eq!(values, &[4]);
+
+    match dict.entry(Color::Red) {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), &Color::Red);
+            assert_eq!(entry.insert(vec![5]), [1, 3]);
+            assert_eq!(entry.remove(), [5]);
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+    match dict.entry(Color::Red) {
+        Entry::Occupied(_) => unreachable!(),
+        Entry::Vacant(entry) => assert_eq!(entry.insert(vec![6]), &[6]),
+    }
+    assert_eq!(dict.len(), 4);
+}
+
+#[test]
+fn test_len() {
+    let mut dict: OptionalDict<Color, u32> = OptionalDict::from_fn(|color| (color != Color::Blue).then_some(1));
+    assert_eq!(dict.len(), 4);
+    dict.insert(Color::Blue, 2);
+    dict[Color::Red] = None;
+    assert_eq!(dict.take(Color::Orange), Some(1));
+    assert_eq!(dict.take(Color::Orange), None);
+    assert_eq!(dict.len(), 3);
+
+    // iterators know how many values they have left
+    let mut iter = dict.iter();
+    assert_eq!(iter.len(), 3);
+    iter.next_back();
+    assert_eq!(iter.len(), 2);
+    assert_eq!(dict.keys().rev().nth(2), Some(Color::Blue));
+    assert_eq!(dict.values_mut().len(), 3);
+    dict.retain(|_, value| *value > 1);
+    assert_eq!(dict.into_iter().len(), 1);
+}
+
+#[test]
+fn test_map_and_zip() {
+    let names: RequiredDict<Locale, String> = RequiredDict::from_fn(|locale: Locale| locale.name().to_string());
+    let lengths = names.clone().map(|name| name.len());
+    assert_eq!(lengths, required_dict! { Locale::English => 7, Locale::Chinese => 7 });
+    let tagged = lengths.map_with_key(|locale, len| format!("{}:{len}", locale.variant_index()));
+    assert_eq!(tagged[Locale::Chinese], "1:7");
+
+    let parsed = required_dict! { Locale::English => "1", Locale::Chinese => "2" }.try_map(str::parse::<u8>);
+    assert_eq!(
+        parsed,
+        Ok(required_dict! { Locale::English => 1, Locale::Chinese => 2 })
+    );
+    let parsed = required_dict! { Locale::English => "1", Locale::Chinese => "x" }.try_map(str::parse::<u8>);
+    assert!(parsed.is_err());
+    let checked = lengths.try_map(|len| len.checked_sub(7));
+    assert_eq!(
+        checked,
+        Some(required_dict! { Locale::English => 0, Locale::Chinese => 0 })
+    );
+
+    let zipped = names.zip(lengths);
+    assert_eq!(zipped[Locale::English], ("english".to_string(), 7));
+    let sums = lengths.zip_with(lengths, |a, b| a + b);
+    assert_eq!(sums[Locale::Chinese], 14);
+
+    let optional: OptionalDict<Color, u32> = [(Color::Red, 1), (Color::Orange, 2), (Color::Custom(Key::A), 3)]
+        .into_iter()
+        .collect();
+    let doubled = optional.map(|value| value * 2);
+    assert_eq!(doubled.values().copied().collect::<Vec<_>>(), [2, 4, 6]);
+    let odd = optional.filter_map(|value| (value % 2 == 1).then_some(value));
+    assert_eq!(odd.keys().collect::<Vec<_>>(), [Color::Red, Color::Custom(Key::A)]);
+    assert_eq!(odd.len(), 2);
+    assert_eq!(optional.try_map(|value| value.checked_sub(2)), None);
+    assert_eq!(
+        optional
+            .try_map(u8::try_from)
+            .unwrap()
+            .map_with_key(|color, value| (color, value)),
+        optional.map_with_key(|color, value| (color, value as u8)),
+    );
+
+    let zipped = optional.zip(odd.map(|value| value * 10));
+    assert_eq!(
+        zipped.into_iter().collect::<Vec<_>>(),
+        [(Color::Red, (1, 10)), (Color::Custom(Key::A), (3, 30))]
+    );
+    assert_eq!(optional.zip_with(doubled, |a, b| a + b).len(), 3);
+}
+
+#[test]
+fn test_parse_error() {
+    let err = "EN".parse::<Locale>().unwrap_err();
+    assert_eq!(err.input(), "EN");
+    assert_eq!(err.key_type(), "Locale");
+    assert_eq!(err.suggestions(), &["en"]);
+    assert_eq!(err.to_string(), "unknown Locale key `EN`, did you mean `en`?");
+    assert_eq!(Locale::aliases(), &["en", "en-us", "zh"]);
+    assert_eq!("en-uk".parse::<Locale>().unwrap_err().suggestions(), &["en-us"]);
+    assert!(Key::aliases().is_empty());
+
+    let err = "englsh".parse::<Locale>().unwrap_err();
+    assert_eq!(err.suggestions(), &["english"]);
+    assert_eq!(err.to_string(), "unknown Locale key `englsh`, did you mean `english`?");
+
+    let err = "c".parse::<Key>().unwrap_err();
+    assert_eq!(err.to_string(), "unknown Key key `c`, did you mean one of `A`, `B`?");
+
+    let err = enum_dict::ParseKeyError::new::<Option<(Key, bool)>>("x");
+    assert_eq!(err.key_type(), "Option<(Key, bool)>");
+
+    let err: Box<dyn std::error::Error> = Box::new(err);
+    assert_eq!(err.to_string(), "unknown Option<(Key, bool)> key `x`");
+}
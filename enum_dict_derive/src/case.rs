@@ -55,6 +55,29 @@ impl RenameRule {
             Self::ScreamingKebabCase => name.to_shouty_kebab_case(),
         }
     }
+
+    /// Apply a renaming rule the way serde does, which differs from `apply` for names with acronyms or digits.
+    pub fn apply_serde(self, name: &str) -> String {
+        match self {
+            Self::None | Self::PascalCase => name.to_owned(),
+            Self::LowerCase => name.to_ascii_lowercase(),
+            Self::UpperCase => name.to_ascii_uppercase(),
+            Self::CamelCase => name[..1].to_ascii_lowercase() + &name[1..],
+            Self::SnakeCase => {
+                let mut snake = String::new();
+                for (i, ch) in name.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            Self::ScreamingSnakeCase => Self::SnakeCase.apply_serde(name).to_ascii_uppercase(),
+            Self::KebabCase => Self::SnakeCase.apply_serde(name).replace('_', "-"),
+            Self::ScreamingKebabCase => Self::ScreamingSnakeCase.apply_serde(name).replace('_', "-"),
+        }
+    }
 }
 
 pub struct ParseError<'a> {
@@ -0,0 +1,179 @@
+use std::path::{Path, PathBuf};
+
+use heck::ToUpperCamelCase;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::punctuated::Punctuated;
+
+use crate::Argument;
+
+/// Input of `dict_keys_from_file!("path", #[attrs] pub enum Name)`
+struct FromFileInput {
+    path: syn::LitStr,
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    ident: syn::Ident,
+}
+
+impl Parse for FromFileInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<syn::Token![enum]>()?;
+        let ident = input.parse()?;
+        input.parse::<Option<syn::Token![,]>>()?;
+        Ok(Self {
+            path,
+            attrs,
+            vis,
+            ident,
+        })
+    }
+}
+
+/// A key read from the file, with the doc comment given by a string value, if any
+struct Entry {
+    name: String,
+    doc: Option<String>,
+}
+
+/// Read the keys of a JSON or TOML file
+///
+/// JSON files may hold an array of names or an object, TOML files a table. Keys of objects and tables are the names,
+/// in file order, and string values become doc comments.
+fn read_entries(path: &syn::LitStr, full_path: &Path) -> syn::Result<Vec<Entry>> {
+    let error = |message: String| syn::Error::new(path.span(), message);
+    let content = std::fs::read_to_string(full_path)
+        .map_err(|err| error(format!("cannot read {}: {err}", full_path.display())))?;
+    match full_path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => {
+            let value: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|err| error(format!("invalid JSON in {}: {err}", path.value())))?;
+            match value {
+                serde_json::Value::Array(values) => values
+                    .into_iter()
+                    .map(|value| match value {
+                        serde_json::Value::String(name) => Ok(Entry { name, doc: None }),
+                        _ => Err(error(format!("expected an array of strings in {}", path.value()))),
+                    })
+                    .collect(),
+                serde_json::Value::Object(map) => Ok(map
+                    .into_iter()
+                    .map(|(name, value)| Entry {
+                        name,
+                        doc: value.as_str().map(str::to_owned),
+                    })
+                    .collect()),
+                _ => Err(error(format!("expected an array or an object in {}", path.value()))),
+            }
+        }
+        Some("toml") => {
+            let table: toml::Table = toml::from_str(&content).map_err(|err| {
+                let position = err.span().map(|span| {
+                    let before = &content[..span.start];
+                    let line = before.matches('\n').count() + 1;
+                    let column = before.len() - before.rfind('\n').map_or(0, |index| index + 1) + 1;
+                    format!(" at line {line} column {column}")
+                });
+                error(format!(
+                    "invalid TOML in {}: {}{}",
+                    path.value(),
+                    err.message(),
+                    position.unwrap_or_default()
+                ))
+            })?;
+            Ok(table
+                .into_iter()
+                .map(|(name, value)| Entry {
+                    name,
+                    doc: value.as_str().map(str::to_owned),
+                })
+                .collect())
+        }
+        _ => Err(error("expected a .json or .toml file".to_owned())),
+    }
+}
+
+/// Find the `crate = "..."` path in the `#[enum_dict(...)]` attributes, so the derive is reached through it as well
+fn crate_path(attrs: &[syn::Attribute]) -> syn::Result<TokenStream2> {
+    for attr in attrs {
+        if !attr.path().is_ident("enum_dict") {
+            continue;
+        }
+        let syn::Meta::List(meta_list) = &attr.meta else {
+            continue;
+        };
+        let args = Punctuated::<Argument, syn::Token![,]>::parse_terminated.parse2(meta_list.tokens.clone())?;
+        for arg in args {
+            if arg.ident == "crate" {
+                let path = arg.lit_str()?.parse::<syn::Path>()?;
+                return Ok(quote! { #path });
+            }
+        }
+    }
+    Ok(quote! { ::enum_dict })
+}
+
+pub fn dict_keys_from_file_inner(input: TokenStream2) -> TokenStream2 {
+    let FromFileInput {
+        path,
+        attrs,
+        vis,
+        ident,
+    } = match syn::parse2(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error(),
+    };
+    let krate = match crate_path(&attrs) {
+        Ok(krate) => krate,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    // like `include_str!`, but relative to the crate root since proc macros cannot see the calling file
+    let full_path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join(path.value());
+    let entries = match read_entries(&path, &full_path) {
+        Ok(entries) => entries,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let mut variants = TokenStream2::new();
+    let mut seen_idents: Vec<(String, &str)> = vec![];
+    for entry in &entries {
+        let variant_name = entry.name.to_upper_camel_case();
+        let Ok(variant) = syn::parse_str::<syn::Ident>(&variant_name) else {
+            let message = format!("`{}` in {} is not a valid variant name", entry.name, path.value());
+            return syn::Error::new(path.span(), message).to_compile_error();
+        };
+        if let Some((_, other)) = seen_idents.iter().find(|(existing, _)| *existing == variant_name) {
+            let message = format!(
+                "`{other}` and `{}` in {} both become the variant `{variant_name}`",
+                entry.name,
+                path.value()
+            );
+            return syn::Error::new(path.span(), message).to_compile_error();
+        }
+        seen_idents.push((variant_name, &entry.name));
+        let name = &entry.name;
+        let doc = entry.doc.iter();
+        variants.extend(quote! {
+            #(#[doc = #doc])*
+            #[enum_dict(rename = #name)]
+            #variant,
+        });
+    }
+
+    let full_path = full_path.to_string_lossy();
+    quote! {
+        #[derive(#krate::DictKey)]
+        #(#attrs)*
+        #vis enum #ident {
+            #variants
+        }
+
+        // rebuild when the file changes
+        const _: &[u8] = include_bytes!(#full_path);
+    }
+}
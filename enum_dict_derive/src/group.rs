@@ -0,0 +1,104 @@
+use heck::ToSnakeCase;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+
+/// `DictKey` functions an inherent group function would shadow
+static RESERVED: &[&str] = &[
+    "variants",
+    "all",
+    "variant_index",
+    "from_variant_index",
+    "from_name",
+    "other",
+    "name",
+    "groups",
+    "group",
+    "doc",
+    "docs",
+    "metadata",
+    "meta",
+];
+
+/// A named subset of variants declared with `#[enum_dict(group = "...")]`
+pub struct Group {
+    pub name: String,
+    /// Name of the generated function returning the members
+    pub fn_ident: syn::Ident,
+    /// Unit members, as `Self::Variant,`
+    units: TokenStream2,
+    /// Statements pushing every member onto `keys`, in index order
+    pushes: TokenStream2,
+    nested: bool,
+}
+
+impl Group {
+    pub fn new(name: &syn::LitStr) -> syn::Result<Self> {
+        let fn_name = name.value().to_snake_case();
+        let fn_ident = match syn::parse_str::<syn::Ident>(&fn_name) {
+            Ok(_) if RESERVED.contains(&fn_name.as_str()) => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("group `{}` would shadow DictKey::{fn_name}", name.value()),
+                ));
+            }
+            Ok(_) => format_ident!("{}", fn_name),
+            Err(_) => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("group `{}` is not a valid function name as `{fn_name}`", name.value()),
+                ));
+            }
+        };
+        Ok(Self {
+            name: name.value(),
+            fn_ident,
+            units: TokenStream2::new(),
+            pushes: TokenStream2::new(),
+            nested: false,
+        })
+    }
+
+    pub fn push_unit(&mut self, ident: &syn::Ident) {
+        self.units.extend(quote! { Self::#ident, });
+        self.pushes.extend(quote! { keys.push(Self::#ident); });
+    }
+
+    pub fn push_nested(&mut self, offset: &TokenStream2, ty: &syn::Type, krate: &TokenStream2) {
+        self.nested = true;
+        self.pushes.extend(quote! {
+            keys.extend(
+                (#offset..#offset + <#ty as #krate::DictKey>::LENGTH)
+                    .map(|index| <Self as #krate::DictKey>::from_variant_index(index).unwrap()),
+            );
+        });
+    }
+
+    pub fn to_fn(&self, enum_ident: &syn::Ident) -> TokenStream2 {
+        let Self {
+            name,
+            fn_ident,
+            units,
+            pushes,
+            nested,
+        } = self;
+        let doc = format!("Keys in the `{name}` group, in index order");
+        let body = if *nested {
+            quote! {
+                static KEYS: ::std::sync::OnceLock<::std::vec::Vec<#enum_ident>> = ::std::sync::OnceLock::new();
+                KEYS.get_or_init(|| {
+                    let mut keys = ::std::vec::Vec::new();
+                    #pushes
+                    keys
+                })
+            }
+        } else {
+            quote! { &[#units] }
+        };
+        quote! {
+            #[doc = #doc]
+            pub fn #fn_ident() -> &'static [Self] {
+                #body
+            }
+        }
+    }
+}
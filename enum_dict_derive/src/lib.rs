@@ -1,24 +1,37 @@
 #![doc = include_str!("../README.md")]
 
+use std::collections::HashMap;
+
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
+use syn::ext::IdentExt;
 use syn::parse::{Parse, Parser};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 
 use crate::case::RenameRule;
+use crate::group::Group;
+use crate::normalize::Normalize;
 
 mod case;
+#[cfg(feature = "from-file")]
+mod from_file;
+mod group;
+mod normalize;
+mod serde_attrs;
 
 struct Argument {
     ident: syn::Ident,
     expr: Option<(syn::Token![=], syn::Expr)>,
+    /// Contents of a `name(...)` argument
+    list: Option<(syn::token::Paren, TokenStream2)>,
 }
 
 impl Parse for Argument {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let name = input.parse()?;
+        // `crate` is a keyword, so accept any identifier
+        let name = syn::Ident::parse_any(input)?;
         let expr = if input.peek(syn::Token![=]) {
             let eq_token: syn::Token![=] = input.parse()?;
             let expr: syn::Expr = input.parse()?;
@@ -26,8 +39,82 @@ impl Parse for Argument {
         } else {
             None
         };
-        Ok(Argument { ident: name, expr })
+        let list = if expr.is_none() && input.peek(syn::token::Paren) {
+            let content;
+            let paren = syn::parenthesized!(content in input);
+            Some((paren, content.parse()?))
+        } else {
+            None
+        };
+        Ok(Argument {
+            ident: name,
+            expr,
+            list,
+        })
+    }
+}
+
+impl Argument {
+    /// Parse the value of a `name = "..."` argument
+    fn lit_str(self) -> syn::Result<syn::LitStr> {
+        match self.expr {
+            Some((
+                _,
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit_str),
+                    ..
+                }),
+            )) => Ok(lit_str),
+            _ => Err(syn::Error::new(
+                self.ident.span(),
+                format!("expected {} = \"...\"", self.ident),
+            )),
+        }
+    }
+
+    /// Check that a flag argument has no value
+    fn flag(self) -> syn::Result<()> {
+        match (self.expr, self.list) {
+            (None, None) => Ok(()),
+            (Some((eq_token, _)), _) => Err(syn::Error::new(
+                eq_token.span(),
+                format!("unexpected value for {}", self.ident),
+            )),
+            (_, Some((paren, _))) => Err(syn::Error::new(
+                paren.span.join(),
+                format!("unexpected arguments for {}", self.ident),
+            )),
+        }
+    }
+
+    /// Parse the arguments of a `name(...)` argument
+    fn list(self) -> syn::Result<Punctuated<Argument, syn::Token![,]>> {
+        match self.list {
+            Some((_, tokens)) => Punctuated::parse_terminated.parse2(tokens),
+            None => Err(syn::Error::new(
+                self.ident.span(),
+                format!("expected {}(...)", self.ident),
+            )),
+        }
+    }
+}
+
+/// Collect the `///` comments of an item, without the leading space each line keeps
+fn doc_comment(attrs: &[syn::Attribute]) -> String {
+    let mut lines = vec![];
+    for attr in attrs {
+        if let syn::Meta::NameValue(name_value) = &attr.meta
+            && name_value.path.is_ident("doc")
+            && let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit_str),
+                ..
+            }) = &name_value.value
+        {
+            let line = lit_str.value();
+            lines.push(line.strip_prefix(' ').unwrap_or(&line).to_owned());
+        }
     }
+    lines.join("\n").trim().to_owned()
 }
 
 #[proc_macro_derive(DictKey, attributes(enum_dict))]
@@ -35,6 +122,13 @@ pub fn derive_dict_key(input: TokenStream) -> TokenStream {
     derive_dict_key_inner(input.into()).into()
 }
 
+/// Declare an enum whose variants are the keys of a JSON or TOML file, see the `enum_dict` docs
+#[cfg(feature = "from-file")]
+#[proc_macro]
+pub fn dict_keys_from_file(input: TokenStream) -> TokenStream {
+    from_file::dict_keys_from_file_inner(input.into()).into()
+}
+
 pub(crate) fn derive_dict_key_inner(input: TokenStream2) -> TokenStream2 {
     let input: syn::DeriveInput = match syn::parse2(input) {
         Ok(input) => input,
@@ -44,17 +138,23 @@ pub(crate) fn derive_dict_key_inner(input: TokenStream2) -> TokenStream2 {
         return syn::Error::new(input.span(), "DictKey can only be derived for enums").to_compile_error();
     };
 
-    let mut rename_all = RenameRule::None;
+    let mut rename_all = None;
+    let mut serde = false;
+    let mut case_insensitive = false;
+    let mut normalize = Normalize::None;
+    let mut krate = quote! { ::enum_dict };
+    let mut from_str = true;
+    let mut display = false;
     let mut errors = TokenStream2::new();
-    for attr in input.attrs {
+    for attr in &input.attrs {
         if !attr.path().is_ident("enum_dict") {
             continue;
         }
-        let syn::Meta::List(meta_list) = attr.meta else {
+        let syn::Meta::List(meta_list) = &attr.meta else {
             errors.extend(syn::Error::new(attr.span(), "expected #[enum_dict(...)]").to_compile_error());
             continue;
         };
-        let args = match Punctuated::<Argument, syn::Token![,]>::parse_terminated.parse2(meta_list.tokens) {
+        let args = match Punctuated::<Argument, syn::Token![,]>::parse_terminated.parse2(meta_list.tokens.clone()) {
             Ok(args) => args,
             Err(err) => {
                 errors.extend(err.to_compile_error());
@@ -63,20 +163,59 @@ pub(crate) fn derive_dict_key_inner(input: TokenStream2) -> TokenStream2 {
         };
         for arg in args {
             if arg.ident == "rename_all" {
-                let Some((
-                    _,
-                    syn::Expr::Lit(syn::ExprLit {
-                        lit: syn::Lit::Str(lit_str),
-                        ..
-                    }),
-                )) = arg.expr
-                else {
-                    errors
-                        .extend(syn::Error::new(arg.ident.span(), "expected rename_all = \"...\"").to_compile_error());
-                    continue;
+                let lit_str = match arg.lit_str() {
+                    Ok(lit_str) => lit_str,
+                    Err(err) => {
+                        errors.extend(err.to_compile_error());
+                        continue;
+                    }
                 };
                 match RenameRule::from_str(&lit_str.value()) {
-                    Ok(rule) => rename_all = rule,
+                    Ok(rule) => rename_all = Some(rule),
+                    Err(err) => errors.extend(syn::Error::new(lit_str.span(), err.to_string()).to_compile_error()),
+                };
+            } else if arg.ident == "crate" {
+                let lit_str = match arg.lit_str() {
+                    Ok(lit_str) => lit_str,
+                    Err(err) => {
+                        errors.extend(err.to_compile_error());
+                        continue;
+                    }
+                };
+                match lit_str.parse::<syn::Path>() {
+                    Ok(path) => krate = quote! { #path },
+                    Err(err) => errors.extend(err.to_compile_error()),
+                }
+            } else if arg.ident == "serde" {
+                match arg.flag() {
+                    Ok(()) => serde = true,
+                    Err(err) => errors.extend(err.to_compile_error()),
+                }
+            } else if arg.ident == "no_from_str" {
+                match arg.flag() {
+                    Ok(()) => from_str = false,
+                    Err(err) => errors.extend(err.to_compile_error()),
+                }
+            } else if arg.ident == "display" {
+                match arg.flag() {
+                    Ok(()) => display = true,
+                    Err(err) => errors.extend(err.to_compile_error()),
+                }
+            } else if arg.ident == "case_insensitive" {
+                match arg.flag() {
+                    Ok(()) => case_insensitive = true,
+                    Err(err) => errors.extend(err.to_compile_error()),
+                }
+            } else if arg.ident == "normalize" {
+                let lit_str = match arg.lit_str() {
+                    Ok(lit_str) => lit_str,
+                    Err(err) => {
+                        errors.extend(err.to_compile_error());
+                        continue;
+                    }
+                };
+                match Normalize::from_str(&lit_str.value()) {
+                    Ok(rule) => normalize = rule,
                     Err(err) => errors.extend(syn::Error::new(lit_str.span(), err.to_string()).to_compile_error()),
                 };
             } else {
@@ -87,27 +226,91 @@ pub(crate) fn derive_dict_key_inner(input: TokenStream2) -> TokenStream2 {
         }
     }
 
+    // in serde mode, `#[serde(rename_all = "...")]` applies unless overridden by `#[enum_dict(rename_all = "...")]`
+    let mut serde_rename_all = (RenameRule::None, RenameRule::None);
+    if serde && rename_all.is_none() {
+        match serde_attrs::parse(&input.attrs, "rename_all") {
+            Ok(names) => {
+                for (lit_str, rule) in [
+                    (names.serialize, &mut serde_rename_all.0),
+                    (names.deserialize, &mut serde_rename_all.1),
+                ] {
+                    let Some(lit_str) = lit_str else {
+                        continue;
+                    };
+                    match RenameRule::from_str(&lit_str.value()) {
+                        Ok(parsed) => *rule = parsed,
+                        Err(err) => errors.extend(syn::Error::new(lit_str.span(), err.to_string()).to_compile_error()),
+                    }
+                }
+            }
+            Err(err) => errors.extend(err.to_compile_error()),
+        }
+    }
+    let rename_all = rename_all.unwrap_or(RenameRule::None);
+
+    // offset of the current variant is `length` plus the lengths of the nested keys seen so far
+    let mut length = 0usize;
+    let mut nested_lengths = TokenStream2::new();
     let mut ident_names = TokenStream2::new();
+    let mut segments = TokenStream2::new();
+    // aliases contribute no index, only candidates for the suggestions of `ParseKeyError`
+    let mut alias_names = TokenStream2::new();
+    let mut alias_segments = TokenStream2::new();
+    let mut has_nested_aliases = false;
+    let mut all_variants = TokenStream2::new();
+    let mut index_arms = TokenStream2::new();
+    let mut variant_arms = TokenStream2::new();
+    // skipped variants have no name, so `Display` writes their Rust name instead
+    let mut skipped_display_arms = TokenStream2::new();
     let mut match_arms = TokenStream2::new();
+    let mut nested_parsers = TokenStream2::new();
+    // names are matched after normalization, so every (normalized) name must belong to a single variant
+    let normalizing = case_insensitive || normalize != Normalize::None;
+    let normalize_tokens = normalize.to_tokens(&krate);
+    let mut seen_names = HashMap::new();
+    // every name and alias as matched by `from_name`, with its variant
+    let mut all_keys = vec![];
+    let mut other_variant: Option<syn::Ident> = None;
+    let mut groups: Vec<Group> = vec![];
+    let mut doc_arms = TokenStream2::new();
+    let mut meta_arms = TokenStream2::new();
+    let mut has_docs = false;
+    let mut has_metadata = false;
     for variant in data.variants {
-        let syn::Fields::Unit = &variant.fields else {
-            errors.extend(
-                syn::Error::new(variant.span(), "DictKey can only be derived for unit variants").to_compile_error(),
-            );
-            continue;
-        };
-
         let ident = &variant.ident;
         let mut name = rename_all.apply(&ident.to_string());
-        for attr in variant.attrs {
+        let mut aliases = vec![];
+        if serde {
+            if serde_rename_all.0 != RenameRule::None {
+                name = serde_rename_all.0.apply_serde(&ident.to_string());
+            }
+            if serde_rename_all.1 != RenameRule::None {
+                aliases.push(serde_rename_all.1.apply_serde(&ident.to_string()));
+            }
+            match serde_attrs::parse(&variant.attrs, "rename") {
+                Ok(names) => {
+                    if let Some(lit_str) = names.serialize {
+                        name = lit_str.value();
+                    }
+                    aliases.extend(names.deserialize.iter().chain(&names.aliases).map(syn::LitStr::value));
+                }
+                Err(err) => errors.extend(err.to_compile_error()),
+            }
+        }
+        let mut skip = false;
+        let mut other = false;
+        let mut variant_groups = vec![];
+        let mut metadata: Vec<(String, String)> = vec![];
+        for attr in &variant.attrs {
             if !attr.path().is_ident("enum_dict") {
                 continue;
             }
-            let syn::Meta::List(meta_list) = attr.meta else {
+            let syn::Meta::List(meta_list) = &attr.meta else {
                 errors.extend(syn::Error::new(attr.span(), "expected #[enum_dict(...)]").to_compile_error());
                 continue;
             };
-            let args = match Punctuated::<Argument, syn::Token![,]>::parse_terminated.parse2(meta_list.tokens) {
+            let args = match Punctuated::<Argument, syn::Token![,]>::parse_terminated.parse2(meta_list.tokens.clone()) {
                 Ok(args) => args,
                 Err(err) => {
                     errors.extend(err.to_compile_error());
@@ -116,19 +319,49 @@ pub(crate) fn derive_dict_key_inner(input: TokenStream2) -> TokenStream2 {
             };
             for arg in args {
                 if arg.ident == "rename" {
-                    let Some((
-                        _,
-                        syn::Expr::Lit(syn::ExprLit {
-                            lit: syn::Lit::Str(lit_str),
-                            ..
-                        }),
-                    )) = arg.expr
-                    else {
-                        errors
-                            .extend(syn::Error::new(arg.ident.span(), "expected rename = \"...\"").to_compile_error());
-                        continue;
+                    match arg.lit_str() {
+                        Ok(lit_str) => name = lit_str.value(),
+                        Err(err) => errors.extend(err.to_compile_error()),
+                    }
+                } else if arg.ident == "alias" {
+                    match arg.lit_str() {
+                        Ok(lit_str) => aliases.push(lit_str.value()),
+                        Err(err) => errors.extend(err.to_compile_error()),
+                    }
+                } else if arg.ident == "group" {
+                    match arg.lit_str() {
+                        Ok(lit_str) => variant_groups.push(lit_str),
+                        Err(err) => errors.extend(err.to_compile_error()),
+                    }
+                } else if arg.ident == "meta" {
+                    let entries = match arg.list() {
+                        Ok(entries) => entries,
+                        Err(err) => {
+                            errors.extend(err.to_compile_error());
+                            continue;
+                        }
                     };
-                    name = lit_str.value();
+                    for entry in entries {
+                        let key = entry.ident.to_string();
+                        let span = entry.ident.span();
+                        match entry.lit_str() {
+                            Ok(_) if metadata.iter().any(|(existing, _)| *existing == key) => errors.extend(
+                                syn::Error::new(span, format!("duplicate meta key `{key}`")).to_compile_error(),
+                            ),
+                            Ok(value) => metadata.push((key, value.value())),
+                            Err(err) => errors.extend(err.to_compile_error()),
+                        }
+                    }
+                } else if arg.ident == "other" {
+                    match arg.flag() {
+                        Ok(()) => other = true,
+                        Err(err) => errors.extend(err.to_compile_error()),
+                    }
+                } else if arg.ident == "skip" {
+                    match arg.flag() {
+                        Ok(()) => skip = true,
+                        Err(err) => errors.extend(err.to_compile_error()),
+                    }
                 } else {
                     errors.extend(
                         syn::Error::new(arg.ident.span(), "unknown attribute for enum_dict derive").to_compile_error(),
@@ -137,8 +370,181 @@ pub(crate) fn derive_dict_key_inner(input: TokenStream2) -> TokenStream2 {
             }
         }
 
-        match_arms.extend(quote! { #name => Ok(Self::#ident), });
-        ident_names.extend(quote! { #name, });
+        let doc = doc_comment(&variant.attrs);
+        has_docs |= !doc.is_empty();
+        has_metadata |= !metadata.is_empty();
+        doc_arms.extend(quote! { Self::#ident { .. } => #doc, });
+        let (meta_keys, meta_values): (Vec<_>, Vec<_>) = metadata.into_iter().unzip();
+        meta_arms.extend(quote! { Self::#ident { .. } => &[#((#meta_keys, #meta_values)),*], });
+
+        if other {
+            let message = if !matches!(variant.fields, syn::Fields::Unit) {
+                Some("#[enum_dict(other)] can only be used on a unit variant".to_owned())
+            } else if skip {
+                Some("#[enum_dict(other)] cannot be combined with #[enum_dict(skip)]".to_owned())
+            } else {
+                other_variant
+                    .as_ref()
+                    .map(|first| format!("`{first}` is already #[enum_dict(other)], only one variant can be"))
+            };
+            match message {
+                Some(message) => errors.extend(syn::Error::new(ident.span(), message).to_compile_error()),
+                None => other_variant = Some(ident.clone()),
+            }
+        }
+
+        if skip && let Some(group) = variant_groups.first() {
+            errors.extend(syn::Error::new(group.span(), "skipped variants cannot be in a group").to_compile_error());
+        }
+
+        if skip {
+            let message = format!(
+                "`{}::{}` is skipped and cannot be used as a dict key",
+                input.ident, ident
+            );
+            variant_arms.extend(quote! { Self::#ident { .. } => ::std::panic!(#message), });
+            let ident_name = ident.to_string();
+            skipped_display_arms.extend(quote! { Self::#ident { .. } => f.write_str(#ident_name), });
+            continue;
+        }
+
+        let ty = match &variant.fields {
+            syn::Fields::Unit => None,
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Some(fields.unnamed[0].ty.clone()),
+            _ => {
+                errors.extend(
+                    syn::Error::new(
+                        variant.span(),
+                        "DictKey can only be derived for unit variants and single-field tuple variants",
+                    )
+                    .to_compile_error(),
+                );
+                continue;
+            }
+        };
+
+        // the name and aliases as matched by `from_name`, without duplicates
+        let mut keys = vec![];
+        for alias in std::iter::once(&name).chain(&aliases) {
+            let normalized = normalize.apply(alias, case_insensitive);
+            if keys.contains(&normalized) {
+                continue;
+            }
+            keys.push(normalized.clone());
+            all_keys.push((normalized.clone(), ident.clone()));
+            if alias != &name {
+                match &ty {
+                    None => {
+                        alias_names.extend(quote! { #alias, });
+                        alias_segments.extend(quote! { #krate::__private::Segment::Unit(#alias), });
+                    }
+                    Some(ty) => {
+                        has_nested_aliases = true;
+                        alias_segments.extend(quote! {
+                            #krate::__private::Segment::Nested(#alias, <#ty as #krate::DictKey>::variants()),
+                        });
+                    }
+                }
+            }
+            let other = seen_names
+                .entry((ty.is_some(), normalized.clone()))
+                .or_insert_with(|| ident.clone());
+            if other != ident {
+                let message = if normalizing {
+                    format!("`{other}` and `{ident}` both match `{normalized}` after normalization")
+                } else {
+                    format!("`{other}` and `{ident}` both have the name `{normalized}`")
+                };
+                errors.extend(syn::Error::new(ident.span(), message).to_compile_error());
+            }
+        }
+
+        let offset = quote! { #length #nested_lengths };
+        for group_name in &variant_groups {
+            let group = match groups.iter().position(|group| group.name == group_name.value()) {
+                Some(position) => &mut groups[position],
+                None => match Group::new(group_name) {
+                    Ok(group) => {
+                        if let Some(other) = groups.iter().find(|other| other.fn_ident == group.fn_ident) {
+                            let message = format!(
+                                "groups `{}` and `{}` would both generate `fn {}`",
+                                other.name, group.name, group.fn_ident
+                            );
+                            errors.extend(syn::Error::new(group_name.span(), message).to_compile_error());
+                            continue;
+                        }
+                        groups.push(group);
+                        groups.last_mut().unwrap()
+                    }
+                    Err(err) => {
+                        errors.extend(err.to_compile_error());
+                        continue;
+                    }
+                },
+            };
+            match &ty {
+                None => group.push_unit(ident),
+                Some(ty) => group.push_nested(&offset, ty, &krate),
+            }
+        }
+        let Some(ty) = ty else {
+            match_arms.extend(quote! { #(#keys)|* => ::std::option::Option::Some(Self::#ident), });
+            ident_names.extend(quote! { #name, });
+            segments.extend(quote! { #krate::__private::Segment::Unit(#name), });
+            all_variants.extend(quote! { Self::#ident, });
+            if nested_lengths.is_empty() {
+                index_arms.extend(quote! { #length => ::std::option::Option::Some(Self::#ident), });
+            } else {
+                index_arms.extend(quote! { index if index == #offset => ::std::option::Option::Some(Self::#ident), });
+            }
+            variant_arms.extend(quote! { Self::#ident => #offset, });
+            length += 1;
+            continue;
+        };
+
+        segments.extend(quote! { #krate::__private::Segment::Nested(#name, <#ty as #krate::DictKey>::variants()), });
+        index_arms.extend(quote! {
+            index if (#offset..#offset + <#ty as #krate::DictKey>::LENGTH).contains(&index) => {
+                <#ty as #krate::DictKey>::from_variant_index(index - (#offset)).map(Self::#ident)
+            }
+        });
+        variant_arms.extend(quote! { Self::#ident(key) => #offset + #krate::DictKey::variant_index(key), });
+        if normalizing {
+            // the rest of the name is matched by the nested key, so only the prefix is normalized
+            nested_parsers.extend(quote! {
+                for (index, _) in name.match_indices('.') {
+                    let prefix = #krate::__private::normalize(&name[..index], #case_insensitive, #normalize_tokens);
+                    if ::std::matches!(prefix.as_str(), #(#keys)|*)
+                        && let ::std::option::Option::Some(key) = <#ty as #krate::DictKey>::from_name(&name[index + 1..])
+                    {
+                        return ::std::option::Option::Some(Self::#ident(key));
+                    }
+                }
+            });
+        } else {
+            for prefix in &keys {
+                let prefix = format!("{prefix}.");
+                nested_parsers.extend(quote! {
+                    if let ::std::option::Option::Some(key) = name.strip_prefix(#prefix).and_then(<#ty as #krate::DictKey>::from_name) {
+                        return ::std::option::Option::Some(Self::#ident(key));
+                    }
+                });
+            }
+        }
+        nested_lengths.extend(quote! { + <#ty as #krate::DictKey>::LENGTH });
+    }
+
+    // names of nested variants are `prefix.inner`, so a name starting with `prefix.` could equal one of them
+    for (key, ident) in &all_keys {
+        for (index, _) in key.match_indices('.') {
+            let prefix = &key[..index];
+            if let Some(other) = seen_names.get(&(true, prefix.to_owned()))
+                && other != ident
+            {
+                let message = format!("the name `{key}` of `{ident}` overlaps the names `{prefix}.*` of `{other}`");
+                errors.extend(syn::Error::new(ident.span(), message).to_compile_error());
+            }
+        }
     }
 
     if !errors.is_empty() {
@@ -146,24 +552,176 @@ pub(crate) fn derive_dict_key_inner(input: TokenStream2) -> TokenStream2 {
     }
 
     let ident = &input.ident;
+    let matched_name = if normalizing {
+        quote! { #krate::__private::normalize(name, #case_insensitive, #normalize_tokens).as_str() }
+    } else {
+        quote! { name }
+    };
+    let items = if nested_lengths.is_empty() {
+        quote! {
+            const LENGTH: usize = #length;
+            type Array<V> = [V; #length];
+            fn variants() -> &'static [&'static str] {
+                &[#ident_names]
+            }
+            fn all() -> &'static [Self] {
+                &[#all_variants]
+            }
+        }
+    } else {
+        quote! {
+            const LENGTH: usize = #length #nested_lengths;
+            type Array<V> = [V; <#ident as #krate::DictKey>::LENGTH];
+            // built on first use; a plain static needs no lookup in the shared table of generic keys
+            fn variants() -> &'static [&'static str] {
+                static NAMES: ::std::sync::OnceLock<::std::vec::Vec<&'static str>> = ::std::sync::OnceLock::new();
+                NAMES.get_or_init(|| #krate::__private::names(&[#segments]))
+            }
+            fn all() -> &'static [Self] {
+                static ALL: ::std::sync::OnceLock<::std::vec::Vec<#ident>> = ::std::sync::OnceLock::new();
+                ALL.get_or_init(#krate::__private::all)
+            }
+        }
+    };
+    let aliases_fn = if alias_segments.is_empty() {
+        TokenStream2::new()
+    } else if has_nested_aliases {
+        quote! {
+            fn aliases() -> &'static [&'static str] {
+                static ALIASES: ::std::sync::OnceLock<::std::vec::Vec<&'static str>> = ::std::sync::OnceLock::new();
+                ALIASES.get_or_init(|| #krate::__private::names(&[#alias_segments]))
+            }
+        }
+    } else {
+        quote! {
+            fn aliases() -> &'static [&'static str] {
+                &[#alias_names]
+            }
+        }
+    };
+    // unknown names parse to the `#[enum_dict(other)]` variant, if any
+    let (other_fn, fallback) = match &other_variant {
+        Some(other) => (
+            quote! {
+                fn other() -> ::std::option::Option<Self> {
+                    ::std::option::Option::Some(Self::#other)
+                }
+            },
+            quote! { ::std::option::Option::Some(Self::#other) },
+        ),
+        None => (TokenStream2::new(), quote! { ::std::option::Option::None }),
+    };
+    let group_impl = (!groups.is_empty()).then(|| {
+        let group_fns = groups.iter().map(|group| group.to_fn(ident));
+        quote! {
+            impl #ident {
+                #(#group_fns)*
+            }
+        }
+    });
+    let group_items = (!groups.is_empty()).then(|| {
+        let names = groups.iter().map(|group| &group.name);
+        let group_arms = groups.iter().map(|group| {
+            let name = &group.name;
+            let fn_ident = &group.fn_ident;
+            quote! { #name => ::std::option::Option::Some(Self::#fn_ident()), }
+        });
+        quote! {
+            fn groups() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+            fn group(name: &str) -> ::std::option::Option<&'static [Self]> {
+                match name {
+                    #(#group_arms)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+    });
+    let doc_fn = has_docs.then(|| {
+        quote! {
+            fn doc(self) -> &'static str {
+                match self {
+                    #doc_arms
+                }
+            }
+        }
+    });
+    let metadata_fn = has_metadata.then(|| {
+        quote! {
+            fn metadata(self) -> &'static [(&'static str, &'static str)] {
+                match self {
+                    #meta_arms
+                }
+            }
+        }
+    });
+    let from_str_impl = from_str.then(|| {
+        quote! {
+            #[automatically_derived]
+            impl ::std::str::FromStr for #ident {
+                type Err = #krate::ParseKeyError;
+                fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                    <Self as #krate::DictKey>::from_name(s).ok_or_else(|| #krate::ParseKeyError::new::<Self>(s))
+                }
+            }
+        }
+    });
+    let display_impl = display.then(|| {
+        let write_name = quote! { f.write_str(<Self as #krate::DictKey>::name(::std::clone::Clone::clone(self))) };
+        let body = if skipped_display_arms.is_empty() {
+            write_name
+        } else {
+            quote! {
+                match self {
+                    #skipped_display_arms
+                    _ => #write_name,
+                }
+            }
+        };
+        quote! {
+            #[automatically_derived]
+            impl ::std::fmt::Display for #ident {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    #body
+                }
+            }
+        }
+    });
     quote! {
         #[automatically_derived]
-        impl ::enum_dict::DictKey for #ident {
-            const VARIANTS: &'static [&'static str] = &[#ident_names];
+        impl #krate::DictKey for #ident {
+            #items
             fn variant_index(self) -> usize {
-                self as usize
+                match self {
+                    #variant_arms
+                }
             }
-        }
-
-        #[automatically_derived]
-        impl ::std::str::FromStr for #ident {
-            type Err = ();
-            fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-                match s {
+            fn from_variant_index(index: usize) -> ::std::option::Option<Self> {
+                match index {
+                    #index_arms
+                    _ => ::std::option::Option::None,
+                }
+            }
+            fn from_name(name: &str) -> ::std::option::Option<Self> {
+                match #matched_name {
                     #match_arms
-                    _ => std::result::Result::Err(()),
+                    _ => {
+                        #nested_parsers
+                        #fallback
+                    }
                 }
             }
+            #aliases_fn
+            #other_fn
+            #group_items
+            #doc_fn
+            #metadata_fn
         }
+
+        #group_impl
+
+        #from_str_impl
+        #display_impl
     }
 }
@@ -0,0 +1,69 @@
+use std::fmt::{Debug, Display};
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+/// How separators are treated when matching key names, mirrored by `enum_dict::__private::Normalize`.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Normalize {
+    None,
+    FoldSeparators,
+    StripSeparators,
+}
+
+static NORMALIZE_RULES: &[(&str, Normalize)] = &[
+    ("fold_separators", Normalize::FoldSeparators),
+    ("strip_separators", Normalize::StripSeparators),
+];
+
+impl Normalize {
+    pub fn from_str(normalize_str: &str) -> Result<Self, ParseError<'_>> {
+        for (name, rule) in NORMALIZE_RULES {
+            if normalize_str == *name {
+                return Ok(*rule);
+            }
+        }
+        Err(ParseError { unknown: normalize_str })
+    }
+
+    /// Apply the rule to a name, after lowercasing it if `case_insensitive` is set.
+    pub fn apply(self, name: &str, case_insensitive: bool) -> String {
+        let name = if case_insensitive {
+            name.to_lowercase()
+        } else {
+            name.to_owned()
+        };
+        match self {
+            Self::None => name,
+            Self::FoldSeparators => name.replace(['-', ' '], "_"),
+            Self::StripSeparators => name.replace(['-', '_', ' '], ""),
+        }
+    }
+
+    pub fn to_tokens(self, krate: &TokenStream2) -> TokenStream2 {
+        match self {
+            Self::None => quote! { #krate::__private::Normalize::None },
+            Self::FoldSeparators => quote! { #krate::__private::Normalize::FoldSeparators },
+            Self::StripSeparators => quote! { #krate::__private::Normalize::StripSeparators },
+        }
+    }
+}
+
+pub struct ParseError<'a> {
+    unknown: &'a str,
+}
+
+impl<'a> Display for ParseError<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("unknown normalize rule `normalize = ")?;
+        Debug::fmt(self.unknown, f)?;
+        f.write_str("`, expected one of ")?;
+        for (i, (name, _rule)) in NORMALIZE_RULES.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            Debug::fmt(name, f)?;
+        }
+        Ok(())
+    }
+}
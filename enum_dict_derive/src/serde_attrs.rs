@@ -0,0 +1,48 @@
+//! Names read from `#[serde(...)]` attributes in `#[enum_dict(serde)]` mode
+
+/// The `rename`/`rename_all` value of a `#[serde(...)]` attribute, plus any `alias = "..."`
+///
+/// `key = "..."` only sets `serialize`, since the name is matched anyway; `deserialize` comes from
+/// `key(deserialize = "...")`.
+#[derive(Default)]
+pub struct SerdeNames {
+    pub serialize: Option<syn::LitStr>,
+    pub deserialize: Option<syn::LitStr>,
+    pub aliases: Vec<syn::LitStr>,
+}
+
+/// Collect the names under `key` from the `#[serde(...)]` attributes, ignoring everything else
+pub fn parse(attrs: &[syn::Attribute], key: &str) -> syn::Result<SerdeNames> {
+    let mut names = SerdeNames::default();
+    for attr in attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) {
+                if meta.input.peek(syn::Token![=]) {
+                    names.serialize = Some(meta.value()?.parse()?);
+                } else {
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("serialize") {
+                            names.serialize = Some(meta.value()?.parse()?);
+                        } else if meta.path.is_ident("deserialize") {
+                            names.deserialize = Some(meta.value()?.parse()?);
+                        } else {
+                            return Err(meta.error("expected serialize or deserialize"));
+                        }
+                        Ok(())
+                    })?;
+                }
+            } else if meta.path.is_ident("alias") {
+                names.aliases.push(meta.value()?.parse()?);
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.input.parse::<proc_macro2::Group>()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(names)
+}